@@ -1,7 +1,7 @@
 import React from 'react';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
 import type { DeckEntry } from '@/types/api';
-import { sortCardEntries, isCommander, isLand } from '@/lib/cardUtils';
+import { sortCardEntries, isCommander, isLand, SECTION_TITLES } from '@/lib/cardUtils';
 
 interface CardDisplayProps {
   entries: DeckEntry[];
@@ -15,9 +15,14 @@ const CardDisplay: React.FC<CardDisplayProps> = ({ entries }) => {
   const sortedEntries = sortCardEntries(entries);
 
   // Group entries by category for display
-  const commanders = sortedEntries.filter(entry => isCommander(entry.categories));
-  const lands = sortedEntries.filter(entry => !isCommander(entry.categories) && isLand(entry.categories));
-  const others = sortedEntries.filter(entry => !isCommander(entry.categories) && !isLand(entry.categories));
+  const commanders = sortedEntries.filter(entry => isCommander(entry));
+  const mainDeck = sortedEntries.filter(entry => entry.section === 'main');
+  const lands = mainDeck.filter(entry => isLand(entry.categories));
+  const others = mainDeck.filter(entry => !isLand(entry.categories));
+  const sideSections = (['companion', 'sideboard', 'maybeboard'] as const).map(section => ({
+    section,
+    entries: sortedEntries.filter(entry => entry.section === section),
+  }));
 
   const renderCardGrid = (cardEntries: DeckEntry[], title?: string) => {
     if (cardEntries.length === 0) return null;
@@ -37,12 +42,12 @@ const CardDisplay: React.FC<CardDisplayProps> = ({ entries }) => {
             <div key={index} className="group">
               <div className="relative">
                 <a
-                  href={`https://scryfall.com/search?q=${encodeURIComponent(entry.card.name)}`}
+                  href={`https://scryfall.com/search?q=${encodeURIComponent(entry.card?.name ?? entry.name)}`}
                   target="_blank"
                   rel="noopener noreferrer"
                   className="block cursor-pointer"
                 >
-                  {entry.card.image_uris?.normal ? (
+                  {entry.card?.image_uris?.normal ? (
                     <img
                       src={entry.card.image_uris.normal}
                       alt={entry.card.name}
@@ -71,7 +76,7 @@ const CardDisplay: React.FC<CardDisplayProps> = ({ entries }) => {
 
               <div className="mt-3 text-center space-y-1">
                 <div className="font-semibold text-sm leading-tight group-hover:mana-gold transition-colors">
-                  {entry.card.name}
+                  {entry.card?.name ?? entry.name}
                 </div>
               </div>
             </div>
@@ -96,6 +101,11 @@ const CardDisplay: React.FC<CardDisplayProps> = ({ entries }) => {
           {commanders.length > 0 && renderCardGrid(commanders, "⚔️ Commanders")}
           {others.length > 0 && renderCardGrid(others, "🎯 Spells & Creatures")}
           {lands.length > 0 && renderCardGrid(lands, "🏔️ Lands")}
+          {sideSections.map(({ section, entries: sectionEntries }) =>
+            sectionEntries.length > 0 && (
+              <React.Fragment key={section}>{renderCardGrid(sectionEntries, SECTION_TITLES[section])}</React.Fragment>
+            )
+          )}
         </CardContent>
       </Card>
     </div>
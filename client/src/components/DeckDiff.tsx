@@ -4,7 +4,7 @@ import { Textarea } from '@/components/ui/textarea';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
 import type { DeckDiffResult, DeckDiffEntry } from '@/types/api';
 import { deckService } from '@/services/api';
-import { sortDiffEntries, isCommander, isLand } from '@/lib/cardUtils';
+import { sortDiffEntries, isCommander, isLand, SECTION_TITLES } from '@/lib/cardUtils';
 
 const DeckDiff: React.FC = () => {
   const [deck1Text, setDeck1Text] = useState('');
@@ -115,6 +115,11 @@ const DeckDiff: React.FC = () => {
             {entry.change_type === 'unchanged' && (
               <span>{entry.old_quantity}x</span>
             )}
+            {entry.change_type === 'moved' && entry.previous_section && (
+              <span className="text-blue-600 dark:text-blue-400">
+                {entry.new_quantity}x {SECTION_TITLES[entry.previous_section]} → {SECTION_TITLES[entry.section]}
+              </span>
+            )}
           </div>
         </div>
       </div>
@@ -131,9 +136,14 @@ const DeckDiff: React.FC = () => {
     const sortedEntries = sortDiffEntries(entries);
 
     // Group entries by category for display
-    const commanders = sortedEntries.filter(entry => isCommander(entry.categories));
-    const lands = sortedEntries.filter(entry => !isCommander(entry.categories) && isLand(entry.categories));
-    const others = sortedEntries.filter(entry => !isCommander(entry.categories) && !isLand(entry.categories));
+    const commanders = sortedEntries.filter(entry => isCommander(entry));
+    const mainDeck = sortedEntries.filter(entry => entry.section === 'main');
+    const lands = mainDeck.filter(entry => isLand(entry.categories));
+    const others = mainDeck.filter(entry => !isLand(entry.categories));
+    const sideSections = (['companion', 'sideboard', 'maybeboard'] as const).map(section => ({
+      section,
+      entries: sortedEntries.filter(entry => entry.section === section),
+    }));
 
     const renderCardGrid = (cardEntries: DeckDiffEntry[], sectionTitle?: string) => {
       if (cardEntries.length === 0) return null;
@@ -169,6 +179,11 @@ const DeckDiff: React.FC = () => {
           {commanders.length > 0 && renderCardGrid(commanders, "⚔️ Commanders")}
           {others.length > 0 && renderCardGrid(others, "🎯 Spells & Creatures")}
           {lands.length > 0 && renderCardGrid(lands, "🏔️ Lands")}
+          {sideSections.map(({ section, entries: sectionEntries }) =>
+            sectionEntries.length > 0 && (
+              <React.Fragment key={section}>{renderCardGrid(sectionEntries, SECTION_TITLES[section])}</React.Fragment>
+            )
+          )}
         </CardContent>
       </Card>
     );
@@ -238,7 +253,7 @@ const DeckDiff: React.FC = () => {
               </CardTitle>
             </CardHeader>
             <CardContent>
-              <div className="grid gap-6 md:grid-cols-4">
+              <div className="grid gap-6 md:grid-cols-5">
                 <div className="text-center p-4 rounded-lg bg-green-500/10 border border-green-500/20">
                   <div className="text-3xl font-bold text-green-600 dark:text-green-400">
                     {diffResult.added.length}
@@ -257,6 +272,12 @@ const DeckDiff: React.FC = () => {
                   </div>
                   <div className="text-sm font-medium text-yellow-700 dark:text-yellow-300">🔄 Modified</div>
                 </div>
+                <div className="text-center p-4 rounded-lg bg-blue-500/10 border border-blue-500/20">
+                  <div className="text-3xl font-bold text-blue-600 dark:text-blue-400">
+                    {diffResult.moved.length}
+                  </div>
+                  <div className="text-sm font-medium text-blue-700 dark:text-blue-300">↔️ Moved</div>
+                </div>
                 <div className="text-center p-4 rounded-lg bg-gray-500/10 border border-gray-500/20">
                   <div className="text-3xl font-bold text-gray-600 dark:text-gray-400">
                     {diffResult.unchanged.length}
@@ -327,6 +348,12 @@ const DeckDiff: React.FC = () => {
             'border-yellow-200 bg-yellow-50 dark:border-yellow-800 dark:bg-yellow-950'
           )}
 
+          {renderDiffSection(
+            'Moved Cards',
+            diffResult.moved,
+            'border-blue-200 bg-blue-50 dark:border-blue-800 dark:bg-blue-950'
+          )}
+
           {renderDiffSection(
             'Unchanged Cards',
             diffResult.unchanged,
@@ -1,9 +1,15 @@
-import type { DeckEntry, DeckDiffEntry } from '@/types/api';
+import type { DeckEntry, DeckDiffEntry, DeckSection } from '@/types/api';
 
-export const isCommander = (categories: string[]): boolean => {
-  return categories.some(category =>
-    category.toLowerCase().includes('commander')
-  );
+export const isCommander = (entry: { section: DeckSection }): boolean => {
+  return entry.section === 'commander';
+};
+
+export const SECTION_TITLES: Record<DeckSection, string> = {
+  commander: 'Commander',
+  companion: 'Companion',
+  main: 'Main Deck',
+  sideboard: 'Sideboard',
+  maybeboard: 'Maybeboard',
 };
 
 export const isLand = (categories: string[]): boolean => {
@@ -14,8 +20,8 @@ export const isLand = (categories: string[]): boolean => {
 
 export const sortCardEntries = (entries: DeckEntry[]): DeckEntry[] => {
   return [...entries].sort((a, b) => {
-    const aIsCommander = isCommander(a.categories);
-    const bIsCommander = isCommander(b.categories);
+    const aIsCommander = isCommander(a);
+    const bIsCommander = isCommander(b);
     const aIsLand = isLand(a.categories);
     const bIsLand = isLand(b.categories);
 
@@ -28,14 +34,14 @@ export const sortCardEntries = (entries: DeckEntry[]): DeckEntry[] => {
     if (!aIsLand && bIsLand) return -1;
 
     // If same category type, sort alphabetically by card name
-    return a.card.name.localeCompare(b.card.name);
+    return a.name.localeCompare(b.name);
   });
 };
 
 export const sortDiffEntries = (entries: DeckDiffEntry[]): DeckDiffEntry[] => {
   return [...entries].sort((a, b) => {
-    const aIsCommander = isCommander(a.categories);
-    const bIsCommander = isCommander(b.categories);
+    const aIsCommander = isCommander(a);
+    const bIsCommander = isCommander(b);
     const aIsLand = isLand(a.categories);
     const bIsLand = isLand(b.categories);
 
@@ -1,5 +1,18 @@
 import axios from 'axios';
-import type { DeckResolveResult, Card, DeckDiffRequest, DeckDiffResult } from '@/types/api';
+import type {
+  DeckResolveResult,
+  Card,
+  DeckDiffRequest,
+  DeckDiffResult,
+  SearchResult,
+  CardIdentifier,
+  BatchResult,
+  PrintingPreference,
+  PrintingsResponse,
+  CardSet,
+  DeckFormat,
+  ExportFormat,
+} from '@/types/api';
 
 const API_BASE_URL = 'http://127.0.0.1:5678';
 
@@ -11,8 +24,13 @@ const api = axios.create({
 });
 
 export const deckService = {
-  async resolveDeck(deckText: string): Promise<DeckResolveResult> {
+  async resolveDeck(
+    deckText: string,
+    preference?: PrintingPreference,
+    format?: DeckFormat
+  ): Promise<DeckResolveResult> {
     const response = await api.post('/deck/resolve', deckText, {
+      params: { ...preference, format },
       headers: {
         'Content-Type': 'text/plain',
       },
@@ -20,8 +38,24 @@ export const deckService = {
     return response.data;
   },
 
-  async diffDecks(request: DeckDiffRequest): Promise<DeckDiffResult> {
-    const response = await api.post('/deck/diff', request);
+  async exportDeck(
+    deckText: string,
+    to: ExportFormat,
+    preference?: PrintingPreference,
+    format?: DeckFormat
+  ): Promise<Blob> {
+    const response = await api.post('/deck/export', deckText, {
+      params: { ...preference, format, to },
+      headers: {
+        'Content-Type': 'text/plain',
+      },
+      responseType: 'blob',
+    });
+    return response.data;
+  },
+
+  async diffDecks(request: DeckDiffRequest, preference?: PrintingPreference): Promise<DeckDiffResult> {
+    const response = await api.post('/deck/diff', request, { params: preference });
     return response.data;
   },
 };
@@ -31,6 +65,69 @@ export const cardService = {
     const response = await api.get(`/cards/${encodeURIComponent(name)}`);
     return response.data;
   },
+
+  async getCardByScryfallId(id: string): Promise<Card> {
+    const response = await api.get(`/cards/id/${encodeURIComponent(id)}`);
+    return response.data;
+  },
+
+  async getCardByOracleId(oracleId: string): Promise<Card> {
+    const response = await api.get(`/cards/oracle/${encodeURIComponent(oracleId)}`);
+    return response.data;
+  },
+
+  async getCardByMtgoId(id: number): Promise<Card> {
+    const response = await api.get(`/cards/mtgo/${id}`);
+    return response.data;
+  },
+
+  async getCardByArenaId(id: number): Promise<Card> {
+    const response = await api.get(`/cards/arena/${id}`);
+    return response.data;
+  },
+
+  async getPrintings(name: string, preference?: PrintingPreference): Promise<PrintingsResponse> {
+    const response = await api.get(`/cards/${encodeURIComponent(name)}/printings`, {
+      params: preference,
+    });
+    return response.data;
+  },
+
+  async getCards(identifiers: CardIdentifier[], preference?: PrintingPreference): Promise<BatchResult> {
+    const response = await api.post('/cards/batch', { identifiers }, { params: preference });
+    return response.data;
+  },
+
+  async autocomplete(query: string, limit?: number): Promise<string[]> {
+    const response = await api.get('/cards/autocomplete', { params: { q: query, limit } });
+    return response.data.names;
+  },
+
+  async search(query: string, page = 1, pageSize?: number): Promise<SearchResult> {
+    const response = await api.get('/cards/search', {
+      params: { q: query, page, page_size: pageSize },
+    });
+    return response.data;
+  },
+};
+
+export const setService = {
+  async getSets(): Promise<CardSet[]> {
+    const response = await api.get('/sets');
+    return response.data;
+  },
+
+  async getSet(code: string): Promise<CardSet> {
+    const response = await api.get(`/sets/${encodeURIComponent(code)}`);
+    return response.data;
+  },
+
+  async getSetCards(code: string, page = 1, pageSize?: number): Promise<SearchResult> {
+    const response = await api.get(`/sets/${encodeURIComponent(code)}/cards`, {
+      params: { page, page_size: pageSize },
+    });
+    return response.data;
+  },
 };
 
 export default api;
\ No newline at end of file
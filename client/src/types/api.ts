@@ -1,44 +1,185 @@
 export interface ImageUris {
+  small: string;
   normal: string;
+  large: string;
+  png: string;
+  art_crop: string;
+  border_crop: string;
+}
+
+export type Color = 'W' | 'U' | 'B' | 'R' | 'G';
+
+export type Rarity = 'common' | 'uncommon' | 'rare' | 'special' | 'mythic' | 'bonus';
+
+export type Legality = 'legal' | 'not_legal' | 'restricted' | 'banned';
+
+export interface Prices {
+  usd?: string;
+  usd_foil?: string;
+  usd_etched?: string;
+  eur?: string;
+  eur_foil?: string;
+  tix?: string;
+}
+
+export interface CardFace {
+  name: string;
+  mana_cost?: string;
+  type_line?: string;
+  oracle_text?: string;
+  power?: string;
+  toughness?: string;
+  loyalty?: string;
+  defense?: string;
+  colors?: Color[];
+  color_indicator?: Color[];
+  artist?: string;
+  image_uris?: ImageUris;
 }
 
 export interface Card {
   id: string;
+  oracle_id?: string;
+  mtgo_id?: number;
+  mtgo_foil_id?: number;
+  arena_id?: number;
   name: string;
+  lang: string;
+  released_at: string;
+  layout: string;
+  mana_cost?: string;
+  cmc?: number;
+  type_line?: string;
+  supertypes: string[];
+  types: string[];
+  subtypes: string[];
+  oracle_text?: string;
+  power?: string;
+  toughness?: string;
+  loyalty?: string;
+  defense?: string;
+  colors?: Color[];
+  color_identity: Color[];
+  color_indicator?: Color[];
+  keywords: string[];
+  legalities: Record<string, Legality>;
+  rarity: Rarity;
+  finishes: string[];
+  promo: boolean;
+  full_art: boolean;
+  frame?: string;
+  prices: Prices;
+  set: string;
+  set_name: string;
+  set_type: string;
+  collector_number: string;
+  artist?: string;
+  scryfall_uri: string;
   image_status: string;
   image_uris?: ImageUris;
+  card_faces?: CardFace[];
 }
 
+export type Finish = 'foil' | 'etched';
+
+export type DeckSection = 'commander' | 'companion' | 'main' | 'sideboard' | 'maybeboard';
+
 export interface DeckEntry {
+  name: string;
   quantity: number;
+  set_code?: string;
+  collector_number?: string;
+  finish?: Finish;
   categories: string[];
-  card: Card;
+  section: DeckSection;
+  card?: Card;
+  set?: CardSet;
 }
 
-export interface ParseError {
+export type ParseErrorSeverity = 'error' | 'warning';
+
+export type ParseErrorKind =
+  | { code: 'missing_quantity' }
+  | { code: 'invalid_quantity' }
+  | { code: 'empty_card_name' }
+  | { code: 'unterminated'; details: { expected: string } }
+  | { code: 'unexpected_text'; details: { text: string } }
+  | { code: 'unknown_finish'; details: { marker: string } }
+  | { code: 'conflicting_finishes' }
+  | { code: 'invalid_file'; details: { message: string } }
+  | { code: 'card_not_found'; details: { name: string } }
+  | { code: 'unknown_set'; details: { set_code: string; name: string } }
+  | { code: 'not_in_set'; details: { set_code: string; name: string } }
+  | {
+      code: 'printing_not_found';
+      details: { set_code: string; collector_number: string; name: string };
+    }
+  | {
+      code: 'printing_mismatch';
+      details: { set_code: string; collector_number: string; printing_name: string; name: string };
+    }
+  | {
+      code: 'name_mismatch';
+      details: { set_code: string; collector_number: string; printing_name: string; name: string };
+    };
+
+export type ParseError = ParseErrorKind & {
   line_number: number;
   line: string;
+  severity: ParseErrorSeverity;
+  /** Character columns in `line`, end exclusive */
+  span: { start: number; end: number };
   error: string;
-}
+  suggestions: string[];
+};
 
 export interface DeckResolveResult {
+  /** The deck's name, for formats that store one */
+  name?: string;
   entries: DeckEntry[];
   total_cards: number;
+  /** Total quantity in each section that has cards */
+  sections: Partial<Record<DeckSection, number>>;
   errors: ParseError[];
+  warnings: ParseError[];
 }
 
 export interface DeckDiffEntry {
   card_name: string;
   old_quantity: number;
   new_quantity: number;
-  change_type: 'added' | 'removed' | 'modified' | 'unchanged';
+  change_type: 'added' | 'removed' | 'modified' | 'unchanged' | 'moved';
+  /** The section the change is in; for moves, the section the cards moved to */
+  section: DeckSection;
+  /** For moves, the section the cards moved from */
+  previous_section?: DeckSection;
+  set_code?: string;
+  collector_number?: string;
+  finish?: Finish;
   card?: Card;
+  set?: CardSet;
   categories: string[];
 }
 
+export type DeckFormat =
+  | 'auto'
+  | 'text'
+  | 'arena'
+  | 'mtgo_dek'
+  | 'mtgo_text'
+  | 'moxfield'
+  | 'archidekt'
+  | 'csv'
+  | 'cockatrice_cod'
+  | 'forge_dck';
+
+export type ExportFormat = 'mtgo_dek' | 'cockatrice_cod' | 'forge_dck';
+
 export interface DeckDiffRequest {
   deck_list_1: string;
   deck_list_2: string;
+  format_1?: DeckFormat;
+  format_2?: DeckFormat;
 }
 
 export interface DeckDiffResult {
@@ -46,6 +187,88 @@ export interface DeckDiffResult {
   removed: DeckDiffEntry[];
   modified: DeckDiffEntry[];
   unchanged: DeckDiffEntry[];
+  moved: DeckDiffEntry[];
   errors_deck_1: ParseError[];
   errors_deck_2: ParseError[];
-}
\ No newline at end of file
+  warnings_deck_1: ParseError[];
+  warnings_deck_2: ParseError[];
+}
+
+export interface SearchResult {
+  total_cards: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+  data: Card[];
+}
+
+export type CardIdentifier =
+  | string
+  | {
+      name?: string;
+      id?: string;
+      oracle_id?: string;
+      mtgo_id?: number;
+      arena_id?: number;
+      set?: string;
+      collector_number?: string;
+    };
+
+export interface BatchMatch {
+  index: number;
+  card: Card;
+  warning?: string;
+}
+
+export interface BatchMiss {
+  index: number;
+  identifier: CardIdentifier;
+  error: string;
+  suggestions: string[];
+}
+
+export interface BatchResult {
+  found: BatchMatch[];
+  not_found: BatchMiss[];
+}
+
+export type PrintingOrder = 'newest' | 'oldest' | 'cheapest';
+
+export interface PrintingPreference {
+  order?: PrintingOrder;
+  non_promo?: boolean;
+  non_full_art?: boolean;
+  frame?: string;
+}
+
+export interface Printing {
+  id: string;
+  set: string;
+  set_name: string;
+  collector_number: string;
+  released_at: string;
+  lang: string;
+  rarity: Rarity;
+  finishes: string[];
+  promo: boolean;
+  full_art: boolean;
+  frame?: string;
+  prices: Prices;
+  image_uris?: ImageUris;
+}
+
+export interface PrintingsResponse {
+  name: string;
+  printings: Printing[];
+}
+
+export interface CardSet {
+  code: string;
+  name: string;
+  set_type: string;
+  released_at?: string;
+  card_count: number;
+  parent_set_code?: string;
+  digital: boolean;
+  icon_svg_uri?: string;
+}
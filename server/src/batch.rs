@@ -0,0 +1,216 @@
+//! Looking up many cards in one request, resolved the same way as deck lines.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::{Card, CardMap};
+use crate::deck::CardQuery;
+use crate::deck_list::CardReference;
+use crate::printings::PrintingPreference;
+
+/// Most identifiers accepted in one batch request.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// One card to look up.
+///
+/// Either a plain string, read like the name in a deck line (so `mtgo:12345` works too), or an
+/// object in the style of Scryfall's `/cards/collection` identifiers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum CardIdentifier {
+    Text(String),
+    Fields(IdentifierFields),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct IdentifierFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Scryfall ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oracle_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtgo_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arena_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+}
+
+impl CardIdentifier {
+    /// The query for this identifier, or `None` when it does not say which card it means.
+    ///
+    /// IDs take precedence over names, and a name over a bare set and collector number.
+    fn query(&self) -> Option<CardQuery<'_>> {
+        let fields = match self {
+            CardIdentifier::Text(name) => {
+                let name = name.trim();
+                return (!name.is_empty()).then(|| CardQuery::from_name(name, None, None));
+            }
+            CardIdentifier::Fields(fields) => fields,
+        };
+
+        let reference = if let Some(id) = &fields.id {
+            Some(CardReference::Scryfall(id))
+        } else if let Some(oracle_id) = &fields.oracle_id {
+            Some(CardReference::Oracle(oracle_id))
+        } else if let Some(mtgo_id) = fields.mtgo_id {
+            Some(CardReference::Mtgo(mtgo_id))
+        } else {
+            fields.arena_id.map(CardReference::Arena)
+        };
+        if let Some(reference) = reference {
+            return Some(CardQuery::Reference(reference));
+        }
+
+        match (&fields.name, &fields.set, &fields.collector_number) {
+            (Some(name), set_code, collector_number) => Some(CardQuery::Name {
+                name,
+                set_code: set_code.as_deref(),
+                collector_number: collector_number.as_deref(),
+            }),
+            (None, Some(set_code), Some(collector_number)) => Some(CardQuery::Printing {
+                set_code,
+                collector_number,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchRequest {
+    pub identifiers: Vec<CardIdentifier>,
+}
+
+/// A card that was found, with its position in the request.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchMatch<'a> {
+    pub index: usize,
+    pub card: &'a Card,
+    /// Set when a requested printing was not found and another printing was used
+    pub warning: Option<String>,
+}
+
+/// An identifier that did not resolve.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchMiss {
+    pub index: usize,
+    pub identifier: CardIdentifier,
+    pub error: String,
+    /// Closest known card names for an unknown name, best match first
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult<'a> {
+    pub found: Vec<BatchMatch<'a>>,
+    pub not_found: Vec<BatchMiss>,
+}
+
+pub fn lookup_cards<'a>(
+    identifiers: Vec<CardIdentifier>,
+    cards: &'a CardMap,
+    preference: &PrintingPreference,
+) -> BatchResult<'a> {
+    let mut found = Vec::new();
+    let mut not_found = Vec::new();
+
+    for (index, identifier) in identifiers.into_iter().enumerate() {
+        let Some(query) = identifier.query() else {
+            not_found.push(BatchMiss {
+                index,
+                identifier,
+                error: "Identifier needs a name, an ID, or a set and collector number".to_string(),
+                suggestions: Vec::new(),
+            });
+            continue;
+        };
+
+        match query.resolve(cards, preference) {
+            Ok((card, warning)) => found.push(BatchMatch {
+                index,
+                card,
+                warning: warning.map(|warning| warning.to_string()),
+            }),
+            Err(suggestions) => not_found.push(BatchMiss {
+                index,
+                identifier,
+                error: "Card not found".to_string(),
+                suggestions,
+            }),
+        }
+    }
+
+    BatchResult { found, not_found }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::CardDatabase;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_lookup_cards_mixes_identifier_kinds() {
+        let card = |name: &str, set: &str, collector_number: &str, mtgo_id: u32| Card {
+            id: format!("{set}-{collector_number}"),
+            name: name.into(),
+            lang: "en".into(),
+            set: set.into(),
+            collector_number: collector_number.to_string(),
+            mtgo_id: Some(mtgo_id),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            card("Lightning Bolt", "m11", "149", 1),
+            card("Lightning Bolt", "2x2", "117", 2),
+            card("Counterspell", "mh2", "267", 3),
+        ]));
+
+        let request: BatchRequest = sonic_rs::from_str(
+            r#"{"identifiers": [
+                "Counterspell",
+                {"name": "Lightning Bolt", "set": "M11"},
+                {"set": "2x2", "collector_number": "117"},
+                {"mtgo_id": 3},
+                "mtgo:2",
+                "Lightnig Bolt",
+                {"set": "m11"}
+            ]}"#,
+        )
+        .unwrap();
+        let result = lookup_cards(request.identifiers, &cards, &PrintingPreference::default());
+
+        let found: Vec<(usize, &str)> = result
+            .found
+            .iter()
+            .map(|found| (found.index, found.card.id.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (0, "mh2-267"),
+                (1, "m11-149"),
+                (2, "2x2-117"),
+                (3, "mh2-267"),
+                (4, "2x2-117")
+            ]
+        );
+
+        assert_eq!(result.not_found.len(), 2);
+        assert_eq!(result.not_found[0].index, 5);
+        assert_eq!(result.not_found[0].suggestions[0], "Lightning Bolt");
+        assert_eq!(result.not_found[1].index, 6);
+        assert_eq!(
+            result.not_found[1].identifier,
+            CardIdentifier::Fields(IdentifierFields {
+                set: Some("m11".to_string()),
+                ..Default::default()
+            })
+        );
+    }
+}
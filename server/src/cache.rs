@@ -0,0 +1,159 @@
+//! Binary snapshots of parsed card data, so restarts skip parsing the Scryfall JSON.
+//!
+//! A snapshot is written next to the bulk file as `<file name>.cache`. Its header records the
+//! cache format version and the size, modification time and a sampled content hash of the JSON it
+//! was built from. A snapshot whose header does not match the current source file is ignored and
+//! rebuilt.
+//!
+//! The hash covers the start, middle and end of the file rather than all of it, so checking a
+//! snapshot stays cheap for multi-gigabyte bulk files. Together with the size it catches files
+//! replaced with the same size and modification time, as `cp -p` or `rsync --times` do, since
+//! every Scryfall export differs in its first cards and in the `updated_at` timestamps.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::cards::Card;
+
+/// Bump whenever `Card` or the snapshot layout changes so old snapshots are rebuilt.
+const CACHE_VERSION: u32 = 4;
+/// How many bytes are hashed at each of the start, middle and end of the source file.
+const SAMPLE_LEN: u64 = 64 * 1024;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CacheHeader {
+    version: u32,
+    source_len: u64,
+    source_modified_nanos: u128,
+    source_hash: u64,
+}
+
+impl CacheHeader {
+    fn for_source(source: &Path) -> std::io::Result<Self> {
+        let mut file = File::open(source)?;
+        let metadata = file.metadata()?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Ok(Self {
+            version: CACHE_VERSION,
+            source_len: metadata.len(),
+            source_modified_nanos: modified.as_nanos(),
+            source_hash: sample_hash(&mut file, metadata.len())?,
+        })
+    }
+}
+
+/// FNV-1a hash of the first, middle and last `SAMPLE_LEN` bytes of a file of `len` bytes.
+fn sample_hash(file: &mut File, len: u64) -> std::io::Result<u64> {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut buffer = Vec::with_capacity(SAMPLE_LEN as usize);
+    let middle = (len / 2).saturating_sub(SAMPLE_LEN / 2);
+    for offset in [0, middle, len.saturating_sub(SAMPLE_LEN)] {
+        buffer.clear();
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut *file).take(SAMPLE_LEN).read_to_end(&mut buffer)?;
+        for &byte in &buffer {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    Ok(hash)
+}
+
+pub fn cache_path(source: &Path) -> PathBuf {
+    let mut file_name = source.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".cache");
+    source.with_file_name(file_name)
+}
+
+/// Reads the snapshot for `source`, returning `Ok(None)` when there is none or it is stale.
+pub fn read_cache(
+    source: &Path,
+) -> Result<Option<Vec<Card>>, Box<dyn std::error::Error + Send + Sync>> {
+    let path = cache_path(source);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let mut reader = BufReader::new(File::open(&path)?);
+    let header: CacheHeader = bincode::deserialize_from(&mut reader)?;
+    if header != CacheHeader::for_source(source)? {
+        return Ok(None);
+    }
+
+    Ok(Some(bincode::deserialize_from(&mut reader)?))
+}
+
+/// Writes a snapshot of `cards` for `source`, replacing any existing one atomically.
+pub fn write_cache(
+    source: &Path,
+    cards: &[Card],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = cache_path(source);
+    let temp_path = path.with_extension("cache.tmp");
+
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    bincode::serialize_into(&mut writer, &CacheHeader::for_source(source)?)?;
+    bincode::serialize_into(&mut writer, cards)?;
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardFace, Color, Legality, Rarity};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_cache_round_trip_and_invalidation() {
+        let dir = std::env::temp_dir().join(format!("mtg-card-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("oracle-cards-20250919090345.json");
+        fs::write(&source, "[]").unwrap();
+
+        let cards = vec![Card {
+            id: "bolt".to_string(),
+            name: "Lightning Bolt".into(),
+            cmc: Some(1.0),
+            colors: Some(vec![Color::R]),
+            rarity: Rarity::Uncommon,
+            legalities: BTreeMap::from([("modern".into(), Legality::Legal)]),
+            card_faces: Some(vec![CardFace {
+                name: "Lightning Bolt".into(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }];
+
+        assert!(read_cache(&source).unwrap().is_none());
+        write_cache(&source, &cards).unwrap();
+        assert_eq!(read_cache(&source).unwrap(), Some(cards.clone()));
+
+        // Changing the source invalidates the snapshot
+        fs::write(&source, "[ ]").unwrap();
+        assert!(read_cache(&source).unwrap().is_none());
+
+        // Even when the new file has the same size and modification time
+        write_cache(&source, &cards).unwrap();
+        let modified = fs::metadata(&source).unwrap().modified().unwrap();
+        fs::write(&source, "[\n]").unwrap();
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert!(read_cache(&source).unwrap().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,61 +1,771 @@
 use sonic_rs::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs::File;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+use crate::cache::{cache_path, read_cache, write_cache};
+use crate::intern::{IStr, Interner};
+use crate::names::{NameIndex, normalize_name};
+use crate::sets::{CardSet, SETS_FILE_NAME, SetCatalog, read_sets_file};
+
+/// A card object from Scryfall's bulk data, see <https://scryfall.com/docs/api/cards>.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Card {
     pub id: String,
-    pub name: String,
-    // pub mana_cost: Option<String>,
-    // pub type_line: String,
-    // pub oracle_text: Option<String>,
-    // pub power: Option<String>,
-    // pub toughness: Option<String>,
-    // pub colors: Option<Vec<String>>,
-    // pub rarity: String,
-    pub image_status: String,
+    pub oracle_id: Option<IStr>,
+    pub mtgo_id: Option<u32>,
+    pub mtgo_foil_id: Option<u32>,
+    pub arena_id: Option<u32>,
+    pub name: IStr,
+    pub lang: IStr,
+    pub released_at: IStr,
+    pub layout: IStr,
+    pub mana_cost: Option<IStr>,
+    pub cmc: Option<f64>,
+    pub type_line: Option<IStr>,
+    // Derived from `type_line` when the cards are loaded
+    #[serde(default)]
+    pub supertypes: Vec<IStr>,
+    #[serde(default)]
+    pub types: Vec<IStr>,
+    #[serde(default)]
+    pub subtypes: Vec<IStr>,
+    pub oracle_text: Option<IStr>,
+    pub power: Option<IStr>,
+    pub toughness: Option<IStr>,
+    pub loyalty: Option<IStr>,
+    pub defense: Option<IStr>,
+    pub colors: Option<Vec<Color>>,
+    #[serde(default)]
+    pub color_identity: Vec<Color>,
+    pub color_indicator: Option<Vec<Color>>,
+    #[serde(default)]
+    pub keywords: Vec<IStr>,
+    #[serde(default)]
+    pub legalities: BTreeMap<IStr, Legality>,
+    pub rarity: Rarity,
+    /// Available finishes: `nonfoil`, `foil` and/or `etched`
+    #[serde(default)]
+    pub finishes: Vec<IStr>,
+    #[serde(default)]
+    pub promo: bool,
+    #[serde(default)]
+    pub full_art: bool,
+    /// Frame edition, such as `1997`, `2015` or `future`
+    pub frame: Option<IStr>,
+    #[serde(default)]
+    pub prices: Prices,
+    pub set: IStr,
+    pub set_name: IStr,
+    pub set_type: IStr,
+    pub collector_number: String,
+    pub artist: Option<IStr>,
+    pub scryfall_uri: String,
+    pub image_status: IStr,
+    pub image_uris: Option<ImageUris>,
+    pub card_faces: Option<Vec<CardFace>>,
+}
+
+/// One face of a multi-faced card (transform, modal double-faced, split, flip, adventure, ...).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct CardFace {
+    pub name: IStr,
+    pub mana_cost: Option<IStr>,
+    pub type_line: Option<IStr>,
+    pub oracle_text: Option<IStr>,
+    pub power: Option<IStr>,
+    pub toughness: Option<IStr>,
+    pub loyalty: Option<IStr>,
+    pub defense: Option<IStr>,
+    pub colors: Option<Vec<Color>>,
+    pub color_indicator: Option<Vec<Color>>,
+    pub artist: Option<IStr>,
     pub image_uris: Option<ImageUris>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct ImageUris {
-    // pub small: String,
+    pub small: String,
     pub normal: String,
-    // pub large: String,
-    // pub png: String,
-    // pub art_crop: String,
-    // pub border_crop: String,
+    pub large: String,
+    pub png: String,
+    pub art_crop: String,
+    pub border_crop: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Color {
+    W,
+    U,
+    B,
+    R,
+    G,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Special,
+    Mythic,
+    Bonus,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Legality {
+    Legal,
+    NotLegal,
+    Restricted,
+    Banned,
+}
+
+/// Market prices as decimal strings, `None` when Scryfall has no price for that finish.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Prices {
+    pub usd: Option<String>,
+    pub usd_foil: Option<String>,
+    pub usd_etched: Option<String>,
+    pub eur: Option<String>,
+    pub eur_foil: Option<String>,
+    pub tix: Option<String>,
+}
+
+const SUPERTYPES: [&str; 7] = [
+    "Basic",
+    "Elite",
+    "Host",
+    "Legendary",
+    "Ongoing",
+    "Snow",
+    "World",
+];
+
+/// Splits a type line such as `Legendary Creature — Human Warrior` into its supertypes, card
+/// types and subtypes, reading every face of a `Front // Back` line and keeping each word once.
+pub fn split_type_line(type_line: &str) -> (Vec<IStr>, Vec<IStr>, Vec<IStr>) {
+    let mut supertypes = Vec::new();
+    let mut types = Vec::new();
+    let mut subtypes = Vec::new();
+
+    fn push_unique(list: &mut Vec<IStr>, word: &str) {
+        if !list.iter().any(|existing| existing == word) {
+            list.push(word.into());
+        }
+    }
+
+    for face in type_line.split(" // ") {
+        let (main, sub) = face.split_once('—').unwrap_or((face, ""));
+        for word in main.split_whitespace() {
+            if SUPERTYPES.contains(&word) {
+                push_unique(&mut supertypes, word);
+            } else {
+                push_unique(&mut types, word);
+            }
+        }
+        for word in sub.split_whitespace() {
+            push_unique(&mut subtypes, word);
+        }
+    }
+
+    (supertypes, types, subtypes)
+}
+
+impl Card {
+    fn is_english(&self) -> bool {
+        self.lang == "en"
+    }
+
+    /// Names of the individual faces, empty for single-faced cards.
+    pub fn face_names(&self) -> impl Iterator<Item = &str> {
+        self.card_faces
+            .iter()
+            .flatten()
+            .map(|face| face.name.as_str())
+    }
+
+    /// Double-faced cards only have images per face, so the front face image is used for the
+    /// card as a whole.
+    fn fill_front_face_image(&mut self) {
+        if self.image_uris.is_none() {
+            self.image_uris = self
+                .card_faces
+                .as_ref()
+                .and_then(|faces| faces.first())
+                .and_then(|face| face.image_uris.clone());
+        }
+    }
+
+    /// Sets the supertypes, types and subtypes from the words before and after the `—` of the type
+    /// line, falling back to the front face's type line for cards that only have one per face.
+    fn split_types(&mut self) {
+        let type_line = self.type_line.as_deref().or_else(|| {
+            self.card_faces
+                .as_ref()
+                .and_then(|faces| faces.first())
+                .and_then(|face| face.type_line.as_deref())
+        });
+        if let Some(type_line) = type_line {
+            (self.supertypes, self.types, self.subtypes) = split_type_line(type_line);
+        }
+    }
+
+    /// Makes the repeated strings of this card share memory with equal strings of other cards.
+    fn intern_strings(&mut self, interner: &mut Interner) {
+        for value in [
+            &mut self.name,
+            &mut self.lang,
+            &mut self.released_at,
+            &mut self.layout,
+            &mut self.set,
+            &mut self.set_name,
+            &mut self.set_type,
+            &mut self.image_status,
+        ] {
+            interner.intern(value);
+        }
+        for value in [
+            &mut self.oracle_id,
+            &mut self.mana_cost,
+            &mut self.type_line,
+            &mut self.oracle_text,
+            &mut self.power,
+            &mut self.toughness,
+            &mut self.loyalty,
+            &mut self.defense,
+            &mut self.artist,
+            &mut self.frame,
+        ] {
+            interner.intern_option(value);
+        }
+        interner.intern_all(&mut self.finishes);
+        interner.intern_all(&mut self.supertypes);
+        interner.intern_all(&mut self.types);
+        interner.intern_all(&mut self.subtypes);
+        interner.intern_all(&mut self.keywords);
+        self.legalities = std::mem::take(&mut self.legalities)
+            .into_iter()
+            .map(|(mut format, legality)| {
+                interner.intern(&mut format);
+                (format, legality)
+            })
+            .collect();
+
+        for face in self.card_faces.iter_mut().flatten() {
+            interner.intern(&mut face.name);
+            for value in [
+                &mut face.mana_cost,
+                &mut face.type_line,
+                &mut face.oracle_text,
+                &mut face.power,
+                &mut face.toughness,
+                &mut face.loyalty,
+                &mut face.defense,
+                &mut face.artist,
+            ] {
+                interner.intern_option(value);
+            }
+        }
+    }
+}
+
+/// The kind of Scryfall bulk export a card file was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkDataKind {
+    /// One card object per Oracle ID
+    OracleCards,
+    /// One card object per unique artwork
+    UniqueArtwork,
+    /// Every printing, in English or the only printed language
+    DefaultCards,
+    /// Every printing in every language
+    AllCards,
+}
+
+impl BulkDataKind {
+    /// Detects the bulk type from Scryfall's file naming, e.g. `default-cards-20250919090345.json`.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        [
+            ("oracle-cards-", BulkDataKind::OracleCards),
+            ("unique-artwork-", BulkDataKind::UniqueArtwork),
+            ("default-cards-", BulkDataKind::DefaultCards),
+            ("all-cards-", BulkDataKind::AllCards),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| file_name.starts_with(prefix))
+        .map(|(_, kind)| kind)
+    }
+}
+
+/// The bulk data file the cards were loaded from.
+#[derive(Debug, Clone, Serialize)]
+pub struct CardDataSource {
+    pub path: PathBuf,
+    pub kind: Option<BulkDataKind>,
+    /// When Scryfall generated the file, taken from its `YYYYMMDDHHMMSS` name suffix
+    pub timestamp: Option<String>,
+}
+
+impl CardDataSource {
+    pub fn from_path(path: PathBuf) -> Self {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let kind = BulkDataKind::from_file_name(file_name);
+        let timestamp = file_timestamp(file_name).map(|digits| {
+            format!(
+                "{}-{}-{}T{}:{}:{}Z",
+                &digits[0..4],
+                &digits[4..6],
+                &digits[6..8],
+                &digits[8..10],
+                &digits[10..12],
+                &digits[12..14]
+            )
+        });
+
+        Self {
+            path,
+            kind,
+            timestamp,
+        }
+    }
+}
+
+/// Extracts the timestamp digits from a bulk file name like `oracle-cards-20250919090345.json`.
+fn file_timestamp(file_name: &str) -> Option<&str> {
+    let stem = file_name.strip_suffix(".json")?;
+    if !stem.contains("-cards-") {
+        return None;
+    }
+    let (_, digits) = stem.rsplit_once('-')?;
+    (digits.len() == 14 && digits.bytes().all(|b| b.is_ascii_digit())).then_some(digits)
+}
+
+/// Resolves a configured card data path to a bulk file.
+///
+/// Files are used as-is. For directories, the `*-cards-*.json` file with the newest timestamp
+/// suffix is picked.
+pub fn find_card_data_file(path: &Path) -> io::Result<PathBuf> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    let mut newest: Option<(String, PathBuf)> = None;
+    for dir_entry in fs::read_dir(path)? {
+        let file_path = dir_entry?.path();
+        let Some(timestamp) = file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(file_timestamp)
+        else {
+            continue;
+        };
+
+        if newest
+            .as_ref()
+            .is_none_or(|(newest_timestamp, _)| timestamp > newest_timestamp.as_str())
+        {
+            newest = Some((timestamp.to_string(), file_path));
+        }
+    }
+
+    newest.map(|(_, file_path)| file_path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no *-cards-*.json file found in {}", path.display()),
+        )
+    })
+}
+
+/// Position of a card in [`CardDatabase`], used by the indexes instead of copies of the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CardId(u32);
+
+/// All loaded cards, indexed by name and by printing.
+///
+/// Any Scryfall bulk export can be loaded. The name index is the oracle-level view: it holds
+/// English printings when they exist, ordered so the first one is the most recent release. The
+/// printing index is the printing-level view and covers every card object in the file.
+///
+/// Every card is stored once. The indexes refer to cards by [`CardId`] and share the interned
+/// name strings of the cards, and lookups hand out references into the database.
+#[derive(Debug, Default)]
+pub struct CardDatabase {
+    source: Option<CardDataSource>,
+    cards: Vec<Card>,
+    // Cards grouped by name to preserve duplicates (especially important for tokens and extra cards)
+    by_name: HashMap<IStr, Vec<CardId>>,
+    // Face names of multi-faced cards mapped to the full `Front // Back` name
+    by_face_name: HashMap<IStr, IStr>,
+    // Normalized card and face names mapped to the full card name
+    by_normalized_name: HashMap<String, IStr>,
+    name_index: NameIndex,
+    // Lowercase set code, then lowercase collector number
+    by_printing: HashMap<IStr, HashMap<String, CardId>>,
+    // UUIDs are stored as numbers, see `parse_uuid`
+    by_scryfall_id: HashMap<u128, CardId>,
+    by_oracle_id: HashMap<u128, CardId>,
+    // Both the regular and the foil MTGO ID
+    by_mtgo_id: HashMap<u32, CardId>,
+    by_arena_id: HashMap<u32, CardId>,
+    sets: SetCatalog,
+}
+
+/// Parses a UUID such as `56ebc372-aabd-4174-a943-c7bf59e5028d` into a number, ignoring case.
+fn parse_uuid(uuid: &str) -> Option<u128> {
+    let hex: String = uuid.chars().filter(|&c| c != '-').collect();
+    if uuid.len() != 36 || hex.len() != 32 {
+        return None;
+    }
+    u128::from_str_radix(&hex, 16).ok()
+}
+
+/// Sorts collector numbers like `2`, `10`, `10a` and `★1` in their printed order.
+fn collector_number_key(collector_number: &str) -> (u32, &str) {
+    let digits_start = collector_number
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(collector_number.len());
+    let digits_end = collector_number[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(collector_number.len(), |end| digits_start + end);
+    let number = collector_number[digits_start..digits_end]
+        .parse()
+        .unwrap_or(u32::MAX);
+    (number, collector_number)
+}
+
+/// Adds a card to an index where translated printings share a key with the English one, which
+/// then wins.
+fn insert_preferring_english<K: Eq + std::hash::Hash>(
+    index: &mut HashMap<K, CardId>,
+    key: K,
+    id: CardId,
+    cards: &[Card],
+) {
+    index
+        .entry(key)
+        .and_modify(|existing| {
+            if !cards[existing.0 as usize].is_english() && cards[id.0 as usize].is_english() {
+                *existing = id;
+            }
+        })
+        .or_insert(id);
+}
+
+impl CardDatabase {
+    pub fn new(mut cards: Vec<Card>) -> Self {
+        let mut interner = Interner::default();
+        for card in &mut cards {
+            card.split_types();
+            card.fill_front_face_image();
+            card.intern_strings(&mut interner);
+        }
+        drop(interner);
+
+        let mut by_name: HashMap<IStr, Vec<CardId>> = HashMap::new();
+        let mut by_printing: HashMap<IStr, HashMap<String, CardId>> = HashMap::new();
+        let mut by_scryfall_id = HashMap::with_capacity(cards.len());
+        let mut by_oracle_id = HashMap::new();
+        let mut by_mtgo_id = HashMap::new();
+        let mut by_arena_id = HashMap::new();
+
+        for (index, card) in cards.iter().enumerate() {
+            let id = CardId(index as u32);
+            by_name.entry(card.name.clone()).or_default().push(id);
+
+            let set = if card.set.chars().any(char::is_uppercase) {
+                IStr::from(card.set.to_lowercase())
+            } else {
+                card.set.clone()
+            };
+            insert_preferring_english(
+                by_printing.entry(set).or_default(),
+                card.collector_number.to_lowercase(),
+                id,
+                &cards,
+            );
+
+            if let Some(scryfall_id) = parse_uuid(&card.id) {
+                by_scryfall_id.insert(scryfall_id, id);
+            }
+            if let Some(oracle_id) = card.oracle_id.as_deref().and_then(parse_uuid) {
+                by_oracle_id.entry(oracle_id).or_insert(id);
+            }
+            for mtgo_id in card.mtgo_id.into_iter().chain(card.mtgo_foil_id) {
+                insert_preferring_english(&mut by_mtgo_id, mtgo_id, id, &cards);
+            }
+            if let Some(arena_id) = card.arena_id {
+                insert_preferring_english(&mut by_arena_id, arena_id, id, &cards);
+            }
+        }
+
+        let card = |id: &CardId| &cards[id.0 as usize];
+        for ids in by_name.values_mut() {
+            if ids.iter().any(|id| card(id).is_english()) {
+                ids.retain(|id| card(id).is_english());
+            }
+            ids.sort_by(|a, b| card(b).released_at.cmp(&card(a).released_at));
+            ids.shrink_to_fit();
+        }
+
+        let mut by_face_name = HashMap::new();
+        let mut by_normalized_name = HashMap::new();
+        for card in &cards {
+            by_normalized_name
+                .entry(normalize_name(&card.name))
+                .or_insert_with(|| card.name.clone());
+        }
+        for card in &cards {
+            for face in card.card_faces.iter().flatten() {
+                // A real card with the same name always wins over a face
+                if face.name != card.name && !by_name.contains_key(&face.name) {
+                    by_face_name
+                        .entry(face.name.clone())
+                        .or_insert_with(|| card.name.clone());
+                    by_normalized_name
+                        .entry(normalize_name(&face.name))
+                        .or_insert_with(|| card.name.clone());
+                }
+            }
+        }
+
+        let name_index = NameIndex::new(
+            by_name.keys().map(|name| (name.as_str(), name)).chain(
+                by_face_name
+                    .iter()
+                    .map(|(face_name, name)| (face_name.as_str(), name)),
+            ),
+        );
+
+        let sets = SetCatalog::new(Vec::new(), &cards);
+
+        Self {
+            source: None,
+            cards,
+            by_name,
+            by_face_name,
+            by_normalized_name,
+            name_index,
+            by_printing,
+            by_scryfall_id,
+            by_oracle_id,
+            by_mtgo_id,
+            by_arena_id,
+            sets,
+        }
+    }
+
+    pub fn with_source(mut self, source: CardDataSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Uses `sets` for the set catalog, keeping sets only known from the cards.
+    pub fn with_sets(mut self, sets: Vec<CardSet>) -> Self {
+        self.sets = SetCatalog::new(sets, &self.cards);
+        self
+    }
+
+    pub fn sets(&self) -> &SetCatalog {
+        &self.sets
+    }
+
+    pub fn source(&self) -> Option<&CardDataSource> {
+        self.source.as_ref()
+    }
+
+    /// Number of unique card names.
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    /// Number of card objects, including every duplicate of a name.
+    pub fn total_cards(&self) -> usize {
+        self.cards.len()
+    }
+
+    fn card(&self, id: CardId) -> &Card {
+        &self.cards[id.0 as usize]
+    }
+
+    /// Every unique card name.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.by_name.keys().map(IStr::as_str)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&Card> {
+        self.printings(name).next()
+    }
+
+    /// Every printing of a card name, most recent first.
+    ///
+    /// Multi-faced cards can be found by their full name or by the name of any face. When there
+    /// is no exact match, the name is compared ignoring case, accents and punctuation.
+    pub fn printings(&self, name: &str) -> impl Iterator<Item = &Card> {
+        self.by_name
+            .get(name)
+            .or_else(|| {
+                self.by_face_name
+                    .get(name)
+                    .or_else(|| self.by_normalized_name.get(&normalize_name(name)))
+                    .and_then(|full_name| self.by_name.get(full_name))
+            })
+            .into_iter()
+            .flatten()
+            .map(|&id| self.card(id))
+    }
+
+    /// The names of up to `limit` cards whose names are closest to `name`, best match first.
+    pub fn suggest_names(&self, name: &str, limit: usize) -> Vec<String> {
+        self.name_index.suggest(name, limit)
+    }
+
+    /// The names of the cards with a name or face name containing a normalized name.
+    pub fn names_containing(&self, normalized: &str) -> HashSet<&str> {
+        self.name_index.names_containing(normalized)
+    }
+
+    /// Up to `limit` card names completing a partially typed name, best match first.
+    pub fn autocomplete_names(&self, query: &str, limit: usize) -> Vec<String> {
+        self.name_index.autocomplete(query, limit)
+    }
+
+    pub fn get_by_printing(&self, set: &str, collector_number: &str) -> Option<&Card> {
+        self.by_printing
+            .get(set.to_lowercase().as_str())?
+            .get(&collector_number.to_lowercase())
+            .map(|&id| self.card(id))
+    }
+
+    /// Every printing in a set, ordered by collector number.
+    pub fn cards_in_set(&self, set: &str) -> Vec<&Card> {
+        let mut cards: Vec<&Card> = self
+            .by_printing
+            .get(set.to_lowercase().as_str())
+            .into_iter()
+            .flat_map(|printings| printings.values())
+            .map(|&id| self.card(id))
+            .collect();
+        cards.sort_by(|a, b| {
+            collector_number_key(&a.collector_number)
+                .cmp(&collector_number_key(&b.collector_number))
+        });
+        cards
+    }
+
+    /// The printings of a card in one set, one per collector number and ordered by it, which is
+    /// how Forge numbers art variants.
+    pub fn printings_in_set(&self, name: &str, set: &str) -> Vec<&Card> {
+        let mut cards: Vec<&Card> = self
+            .printings(name)
+            .filter(|card| card.set.eq_ignore_ascii_case(set))
+            .collect();
+        cards.sort_by(|a, b| {
+            collector_number_key(&a.collector_number)
+                .cmp(&collector_number_key(&b.collector_number))
+        });
+        cards.dedup_by(|a, b| a.collector_number == b.collector_number);
+        cards
+    }
+
+    /// Looks up a printing by its Scryfall ID.
+    pub fn get_by_scryfall_id(&self, scryfall_id: &str) -> Option<&Card> {
+        self.by_scryfall_id
+            .get(&parse_uuid(scryfall_id)?)
+            .map(|&id| self.card(id))
+    }
+
+    /// Looks up a card by its Oracle ID, returning the same printing as a lookup by name.
+    pub fn get_by_oracle_id(&self, oracle_id: &str) -> Option<&Card> {
+        let any_printing = self.card(*self.by_oracle_id.get(&parse_uuid(oracle_id)?)?);
+        self.get_by_name(&any_printing.name)
+    }
+
+    /// Looks up a printing by its MTGO catalog ID, regular or foil.
+    pub fn get_by_mtgo_id(&self, mtgo_id: u32) -> Option<&Card> {
+        self.by_mtgo_id.get(&mtgo_id).map(|&id| self.card(id))
+    }
+
+    /// Looks up a printing by its MTG Arena ID.
+    pub fn get_by_arena_id(&self, arena_id: u32) -> Option<&Card> {
+        self.by_arena_id.get(&arena_id).map(|&id| self.card(id))
+    }
 }
 
-pub type CardMap = Arc<HashMap<String, Vec<Card>>>;
+pub type CardMap = Arc<CardDatabase>;
 
+/// Loads the cards from a bulk data file, or the newest one in a directory.
+///
+/// With `use_cache`, a binary snapshot next to the JSON is used when it is up to date, and
+/// written after parsing the JSON otherwise.
 #[instrument]
-pub fn load_cards() -> Result<CardMap, Box<dyn std::error::Error>> {
+pub fn load_cards(
+    path: &Path,
+    use_cache: bool,
+) -> Result<CardMap, Box<dyn std::error::Error + Send + Sync>> {
     let load_start = Instant::now();
 
-    info!("Loading cards from Scryfall JSON data...");
+    let source = CardDataSource::from_path(find_card_data_file(path)?);
+    info!(
+        path = %source.path.display(),
+        kind = ?source.kind,
+        timestamp = ?source.timestamp,
+        "Loading cards from Scryfall JSON data..."
+    );
+
+    let cached = if use_cache {
+        read_cache(&source.path).unwrap_or_else(|error| {
+            warn!(%error, "Ignoring unreadable card cache");
+            None
+        })
+    } else {
+        None
+    };
+    let from_cache = cached.is_some();
 
-    let file = File::open("data/oracle-cards-20250919090345.json")?;
-    let cards: Vec<Card> = sonic_rs::from_reader(file)?;
+    let cards = match cached {
+        Some(cards) => cards,
+        None => {
+            let file = File::open(&source.path)?;
+            sonic_rs::from_reader(file)?
+        }
+    };
+    let source_path = source.path.clone();
+    let mut card_map = CardDatabase::new(cards).with_source(source);
 
-    // Group cards by name to preserve duplicates (especially important for tokens and extra cards)
-    let mut card_map: HashMap<String, Vec<Card>> = HashMap::new();
-    for card in cards {
-        card_map
-            .entry(card.name.clone())
-            .or_insert_with(Vec::new)
-            .push(card);
+    let sets_path = source_path.with_file_name(SETS_FILE_NAME);
+    if sets_path.exists() {
+        match read_sets_file(&sets_path) {
+            Ok(sets) => card_map = card_map.with_sets(sets),
+            Err(error) => {
+                warn!(%error, path = %sets_path.display(), "Ignoring unreadable set list")
+            }
+        }
+    }
+
+    if use_cache && !from_cache {
+        match write_cache(&source_path, &card_map.cards) {
+            Ok(()) => info!(path = %cache_path(&source_path).display(), "Wrote card cache"),
+            Err(error) => warn!(%error, "Failed to write card cache"),
+        }
     }
 
     let load_duration = load_start.elapsed();
-    let unique_names = card_map.len();
-    let total_cards: usize = card_map.values().map(|cards| cards.len()).sum();
     info!(
-        unique_names = unique_names,
-        total_cards = total_cards,
+        unique_names = card_map.len(),
+        total_cards = card_map.total_cards(),
+        sets = card_map.sets().all().len(),
+        from_cache,
         load_time_ms = load_duration.as_millis(),
         "Successfully loaded cards"
     );
@@ -64,7 +774,16 @@ pub fn load_cards() -> Result<CardMap, Box<dyn std::error::Error>> {
 }
 
 pub fn get_card_by_name<'a>(cards: &'a CardMap, name: &str) -> Option<&'a Card> {
-    cards.get(name).and_then(|card_vec| card_vec.first())
+    cards.get_by_name(name)
+}
+
+/// Looks up a specific printing by set code and collector number, ignoring case.
+pub fn get_card_by_printing<'a>(
+    cards: &'a CardMap,
+    set: &str,
+    collector_number: &str,
+) -> Option<&'a Card> {
+    cards.get_by_printing(set, collector_number)
 }
 
 #[cfg(test)]
@@ -73,22 +792,21 @@ mod tests {
 
     #[test]
     fn test_get_card_by_name_returns_none_for_missing_card() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let result = get_card_by_name(&cards, "Nonexistent Card");
         assert!(result.is_none());
     }
 
     #[test]
     fn test_get_card_by_name_returns_card_when_found() {
-        let mut card_map = HashMap::new();
         let test_card = Card {
             id: "test-id".to_string(),
-            name: "Lightning Bolt".to_string(),
-            image_status: "highres_scan".to_string(),
+            name: "Lightning Bolt".into(),
+            image_status: "highres_scan".into(),
             image_uris: None,
+            ..Default::default()
         };
-        card_map.insert("Lightning Bolt".to_string(), vec![test_card.clone()]);
-        let cards = Arc::new(card_map);
+        let cards = Arc::new(CardDatabase::new(vec![test_card]));
 
         let result = get_card_by_name(&cards, "Lightning Bolt");
         assert!(result.is_some());
@@ -98,20 +816,346 @@ mod tests {
     }
 
     #[test]
-    fn test_card_name_case_sensitivity() {
-        let mut card_map = HashMap::new();
+    fn test_card_name_lookup_ignores_case_and_accents() {
         let test_card = Card {
             id: "test-id".to_string(),
-            name: "Lightning Bolt".to_string(),
-            image_status: "highres_scan".to_string(),
+            name: "Lightning Bolt".into(),
+            image_status: "highres_scan".into(),
             image_uris: None,
+            ..Default::default()
+        };
+        let vault = Card {
+            id: "vault-id".to_string(),
+            name: "Lim-Dûl's Vault".into(),
+            ..Default::default()
         };
-        card_map.insert("Lightning Bolt".to_string(), vec![test_card]);
-        let cards = Arc::new(card_map);
+        let cards = Arc::new(CardDatabase::new(vec![test_card, vault]));
 
         assert!(get_card_by_name(&cards, "Lightning Bolt").is_some());
-        assert!(get_card_by_name(&cards, "lightning bolt").is_none());
-        assert!(get_card_by_name(&cards, "LIGHTNING BOLT").is_none());
+        assert!(get_card_by_name(&cards, "lightning bolt").is_some());
+        assert!(get_card_by_name(&cards, "LIGHTNING BOLT").is_some());
+        assert!(get_card_by_name(&cards, "Lightning Bolts").is_none());
+
+        for name in ["Lim-Dul's Vault", "lim-dûl’s vault", "Lim Duls Vault"] {
+            assert_eq!(get_card_by_name(&cards, name).unwrap().id, "vault-id");
+        }
     }
-}
 
+    #[test]
+    fn test_get_card_by_printing() {
+        let forest = |id: &str, set: &str, collector_number: &str| Card {
+            id: id.to_string(),
+            name: "Forest".into(),
+            set: set.into(),
+            collector_number: collector_number.to_string(),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            forest("forest-tla", "tla", "286"),
+            forest("forest-bfz", "bfz", "251"),
+        ]));
+
+        assert_eq!(
+            get_card_by_printing(&cards, "tla", "286").unwrap().id,
+            "forest-tla"
+        );
+        assert_eq!(
+            get_card_by_printing(&cards, "BFZ", "251").unwrap().id,
+            "forest-bfz"
+        );
+        assert!(get_card_by_printing(&cards, "bfz", "286").is_none());
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards.total_cards(), 2);
+
+        // Both printings share one copy of the name
+        let [first, second] = [&cards.cards[0], &cards.cards[1]];
+        assert!(std::ptr::eq(first.name.as_str(), second.name.as_str()));
+    }
+
+    #[test]
+    fn test_cards_in_set_sorted_by_collector_number() {
+        let printing = |collector_number: &str| Card {
+            id: collector_number.to_string(),
+            name: format!("Card {}", collector_number).into(),
+            set: "tla".into(),
+            collector_number: collector_number.to_string(),
+            ..Default::default()
+        };
+        let cards = CardDatabase::new(vec![
+            printing("10"),
+            printing("2a"),
+            printing("2"),
+            printing("★1"),
+        ]);
+
+        let ids: Vec<&str> = cards
+            .cards_in_set("TLA")
+            .iter()
+            .map(|card| card.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["★1", "2", "2a", "10"]);
+        assert!(cards.cards_in_set("bfz").is_empty());
+    }
+
+    #[test]
+    fn test_name_index_prefers_recent_english_printings() {
+        let printing = |id: &str, lang: &str, released_at: &str, collector_number: &str| Card {
+            id: id.to_string(),
+            name: "Sol Ring".into(),
+            lang: lang.into(),
+            released_at: released_at.into(),
+            set: "c21".into(),
+            collector_number: collector_number.to_string(),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            printing("c21-de", "de", "2021-04-23", "263"),
+            printing("c21-en", "en", "2021-04-23", "263"),
+            printing("c21-en-old", "en", "1993-08-05", "264"),
+            printing("c21-en-new", "en", "2024-01-01", "265"),
+        ]));
+
+        let ids: Vec<_> = cards
+            .printings("Sol Ring")
+            .map(|card| card.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c21-en-new", "c21-en", "c21-en-old"]);
+        assert_eq!(
+            get_card_by_name(&cards, "Sol Ring").unwrap().id,
+            "c21-en-new"
+        );
+        assert_eq!(
+            get_card_by_printing(&cards, "c21", "263").unwrap().id,
+            "c21-en"
+        );
+        assert_eq!(cards.total_cards(), 4);
+    }
+
+    #[test]
+    fn test_lookup_by_ids() {
+        let printing = |id: &str, released_at: &str, mtgo_id: u32, arena_id: Option<u32>| Card {
+            id: id.to_string(),
+            oracle_id: Some("4cbc6901-6a4a-4d0a-83ea-7eefa3b35021".into()),
+            mtgo_id: Some(mtgo_id),
+            mtgo_foil_id: Some(mtgo_id + 1),
+            arena_id,
+            name: "Sol Ring".into(),
+            lang: "en".into(),
+            released_at: released_at.into(),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            printing(
+                "6ad8011d-3471-4369-9d68-b264cc027487",
+                "2010-07-16",
+                37996,
+                None,
+            ),
+            printing(
+                "ea1feac0-d3a7-45eb-9719-1cdaf51ea0b6",
+                "2024-01-01",
+                120000,
+                Some(87000),
+            ),
+        ]));
+        let new_printing = "ea1feac0-d3a7-45eb-9719-1cdaf51ea0b6";
+
+        assert_eq!(
+            cards
+                .get_by_scryfall_id("6AD8011D-3471-4369-9D68-B264CC027487")
+                .unwrap()
+                .mtgo_id,
+            Some(37996)
+        );
+        assert!(cards.get_by_scryfall_id("6ad8011d").is_none());
+        assert_eq!(
+            cards
+                .get_by_oracle_id("4cbc6901-6a4a-4d0a-83ea-7eefa3b35021")
+                .unwrap()
+                .id,
+            new_printing
+        );
+        assert_eq!(cards.get_by_mtgo_id(37997).unwrap().mtgo_id, Some(37996));
+        assert_eq!(cards.get_by_arena_id(87000).unwrap().id, new_printing);
+        assert!(cards.get_by_arena_id(1).is_none());
+    }
+
+    #[test]
+    fn test_bulk_data_kind_from_file_name() {
+        assert_eq!(
+            BulkDataKind::from_file_name("oracle-cards-20250919090345.json"),
+            Some(BulkDataKind::OracleCards)
+        );
+        assert_eq!(
+            BulkDataKind::from_file_name("all-cards-20250919090345.json"),
+            Some(BulkDataKind::AllCards)
+        );
+        assert_eq!(BulkDataKind::from_file_name("cards.json"), None);
+    }
+
+    #[test]
+    fn test_card_data_source_from_path() {
+        let source =
+            CardDataSource::from_path(PathBuf::from("data/default-cards-20250919090345.json"));
+        assert_eq!(source.kind, Some(BulkDataKind::DefaultCards));
+        assert_eq!(source.timestamp.as_deref(), Some("2025-09-19T09:03:45Z"));
+
+        let source = CardDataSource::from_path(PathBuf::from("cards.json"));
+        assert_eq!(source.kind, None);
+        assert_eq!(source.timestamp, None);
+    }
+
+    #[test]
+    fn test_find_card_data_file_picks_newest() {
+        let dir = std::env::temp_dir().join(format!("mtg-card-data-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert!(find_card_data_file(&dir).is_err());
+
+        for name in [
+            "oracle-cards-20250919090345.json",
+            "default-cards-20251001090000.json",
+            "all-cards-20240101000000.json",
+            "sets.json",
+        ] {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        let found = find_card_data_file(&dir).unwrap();
+        assert_eq!(found, dir.join("default-cards-20251001090000.json"));
+
+        let file = dir.join("oracle-cards-20250919090345.json");
+        assert_eq!(find_card_data_file(&file).unwrap(), file);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_split_type_line() {
+        let (supertypes, types, subtypes) = split_type_line("Legendary Creature — Human Warrior");
+        assert_eq!(supertypes, vec!["Legendary"]);
+        assert_eq!(types, vec!["Creature"]);
+        assert_eq!(subtypes, vec!["Human", "Warrior"]);
+
+        let (supertypes, types, subtypes) = split_type_line("Basic Snow Land — Forest");
+        assert_eq!(supertypes, vec!["Basic", "Snow"]);
+        assert_eq!(types, vec!["Land"]);
+        assert_eq!(subtypes, vec!["Forest"]);
+
+        let (supertypes, types, subtypes) = split_type_line(
+            "Legendary Creature — Human Avatar // Legendary Artifact Creature — Avatar",
+        );
+        assert_eq!(supertypes, vec!["Legendary"]);
+        assert_eq!(types, vec!["Creature", "Artifact"]);
+        assert_eq!(subtypes, vec!["Human", "Avatar"]);
+    }
+
+    #[test]
+    fn test_deserialize_scryfall_card() {
+        let json = r#"{
+            "object": "card",
+            "id": "e3285e6b-3e79-4d7c-bf96-d920f973b80b",
+            "oracle_id": "4457ed35-7c10-48c8-9776-456485fdf070",
+            "name": "Lightning Bolt",
+            "lang": "en",
+            "released_at": "2010-07-16",
+            "layout": "normal",
+            "mana_cost": "{R}",
+            "cmc": 1.0,
+            "type_line": "Instant",
+            "oracle_text": "Lightning Bolt deals 3 damage to any target.",
+            "colors": ["R"],
+            "color_identity": ["R"],
+            "keywords": [],
+            "legalities": {"modern": "legal", "standard": "not_legal", "vintage": "restricted"},
+            "rarity": "common",
+            "prices": {"usd": "1.99", "usd_foil": null, "usd_etched": null, "eur": "1.50", "eur_foil": null, "tix": "0.02"},
+            "set": "m11",
+            "set_name": "Magic 2011",
+            "set_type": "core",
+            "collector_number": "149",
+            "artist": "Christopher Moeller",
+            "scryfall_uri": "https://scryfall.com/card/m11/149/lightning-bolt",
+            "image_status": "highres_scan",
+            "image_uris": {
+                "small": "https://cards.scryfall.io/small/front/e/3/e3285e6b.jpg",
+                "normal": "https://cards.scryfall.io/normal/front/e/3/e3285e6b.jpg",
+                "large": "https://cards.scryfall.io/large/front/e/3/e3285e6b.jpg",
+                "png": "https://cards.scryfall.io/png/front/e/3/e3285e6b.png",
+                "art_crop": "https://cards.scryfall.io/art_crop/front/e/3/e3285e6b.jpg",
+                "border_crop": "https://cards.scryfall.io/border_crop/front/e/3/e3285e6b.jpg"
+            }
+        }"#;
+
+        let card: Card = sonic_rs::from_str(json).unwrap();
+        let cards = CardDatabase::new(vec![card]);
+        let card = cards.get_by_name("Lightning Bolt").unwrap();
+
+        assert_eq!(card.mana_cost.as_deref(), Some("{R}"));
+        assert_eq!(card.cmc, Some(1.0));
+        assert_eq!(card.types, vec!["Instant"]);
+        assert_eq!(card.colors, Some(vec![Color::R]));
+        assert_eq!(card.rarity, Rarity::Common);
+        assert_eq!(card.legalities["vintage"], Legality::Restricted);
+        assert_eq!(card.prices.usd.as_deref(), Some("1.99"));
+        assert_eq!(card.set_name, "Magic 2011");
+        assert!(
+            card.image_uris
+                .as_ref()
+                .unwrap()
+                .art_crop
+                .contains("art_crop")
+        );
+        assert!(card.card_faces.is_none());
+    }
+
+    #[test]
+    fn test_multi_faced_card_lookup() {
+        let face = |name: &str, image: &str| CardFace {
+            name: name.into(),
+            image_uris: Some(ImageUris {
+                normal: image.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let aang = Card {
+            name: "Aang, at the Crossroads // Aang, Destined Savior".into(),
+            layout: "transform".into(),
+            card_faces: Some(vec![
+                face("Aang, at the Crossroads", "front-url"),
+                face("Aang, Destined Savior", "back-url"),
+            ]),
+            ..Default::default()
+        };
+        let stomp = Card {
+            name: "Stomp".into(),
+            ..Default::default()
+        };
+        let giant = Card {
+            name: "Bonecrusher Giant // Stomp".into(),
+            layout: "adventure".into(),
+            card_faces: Some(vec![face("Bonecrusher Giant", ""), face("Stomp", "")]),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![aang, stomp, giant]));
+
+        for name in [
+            "Aang, at the Crossroads // Aang, Destined Savior",
+            "Aang, at the Crossroads",
+            "Aang, Destined Savior",
+        ] {
+            let card = get_card_by_name(&cards, name).unwrap();
+            assert_eq!(
+                card.name,
+                "Aang, at the Crossroads // Aang, Destined Savior"
+            );
+            assert_eq!(card.image_uris.as_ref().unwrap().normal, "front-url");
+        }
+
+        assert_eq!(
+            get_card_by_name(&cards, "Bonecrusher Giant").unwrap().name,
+            "Bonecrusher Giant // Stomp"
+        );
+        assert_eq!(get_card_by_name(&cards, "Stomp").unwrap().name, "Stomp");
+    }
+}
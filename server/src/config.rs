@@ -0,0 +1,69 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// MTG deck viewer and diff server
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Config {
+    /// Scryfall bulk data file, or a directory to load the newest `*-cards-*.json` file from
+    #[arg(long, env = "MTG_CARD_DATA", default_value = "data", global = true)]
+    pub card_data: PathBuf,
+
+    /// Always parse the JSON instead of reading or writing a binary snapshot next to it
+    #[arg(long, env = "MTG_NO_CARD_CACHE")]
+    pub no_card_cache: bool,
+
+    /// Token that `POST /admin/reload` requires as `Authorization: Bearer <token>`; without one,
+    /// the endpoint is disabled and card data is only reloaded on SIGHUP
+    #[arg(long, env = "MTG_ADMIN_TOKEN")]
+    pub admin_token: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Download the latest Scryfall bulk data file into the card data directory and exit
+    RefreshData(RefreshArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RefreshArgs {
+    /// Which bulk export to download
+    #[arg(long, env = "MTG_BULK_TYPE", value_enum, default_value_t = BulkType::OracleCards)]
+    pub bulk_type: BulkType,
+
+    /// Base URL of the Scryfall API, or of a mirror serving the same `/bulk-data` manifest
+    #[arg(
+        long,
+        env = "MTG_SCRYFALL_API",
+        default_value = "https://api.scryfall.com"
+    )]
+    pub base_url: String,
+
+    /// How many files of the downloaded bulk type to keep, including the new one
+    #[arg(long, env = "MTG_KEEP_DATA_FILES", default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub keep: u32,
+}
+
+/// Bulk exports with one card object per entry, see <https://scryfall.com/docs/api/bulk-data>.
+// Variant names mirror Scryfall's bulk types and become the CLI values
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BulkType {
+    OracleCards,
+    DefaultCards,
+    AllCards,
+}
+
+impl BulkType {
+    /// The `type` of the bulk data object in Scryfall's manifest.
+    pub fn api_name(self) -> &'static str {
+        match self {
+            BulkType::OracleCards => "oracle_cards",
+            BulkType::DefaultCards => "default_cards",
+            BulkType::AllCards => "all_cards",
+        }
+    }
+}
@@ -1,257 +1,554 @@
-use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::cards::{Card, CardMap, get_card_by_name};
+use crate::cards::{Card, CardMap, get_card_by_printing};
+use crate::deck_list::{
+    CardReference, Finish, ParseError, ParseErrorKind, ParsedDeckList, Section, Span,
+};
+use crate::formats::DeckFormat;
+use crate::printings::PrintingPreference;
+use crate::sets::CardSet;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct DeckEntry {
-    pub categories: Vec<String>,
+/// A parsed deck line. The resolved card is borrowed from the card database.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DeckEntry<'a> {
+    pub name: String,
     pub quantity: u32,
-    pub card: Card,
+    pub set_code: Option<String>,
+    pub collector_number: Option<String>,
+    pub finish: Option<Finish>,
+    pub categories: Vec<String>,
+    pub section: Section,
+    pub card: Option<&'a Card>,
+    /// The set of the resolved printing
+    pub set: Option<&'a CardSet>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ParseError {
-    pub line_number: usize,
-    pub line: String,
-    pub error: String,
+impl DeckEntry<'_> {
+    /// The canonical card name if the entry resolved, otherwise the name as written.
+    pub fn card_name(&self) -> &str {
+        self.card.map_or(&self.name, |card| &card.name)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeckResolveResult {
-    pub entries: Vec<DeckEntry>,
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckResolveResult<'a> {
+    /// The deck's name, for formats that store one
+    pub name: Option<String>,
+    pub entries: Vec<DeckEntry<'a>>,
     pub total_cards: u32,
+    /// Total quantity in each section that has cards
+    pub sections: BTreeMap<Section, u32>,
     pub errors: Vec<ParseError>,
+    pub warnings: Vec<ParseError>,
 }
 
-pub fn resolve_deck_list(input: &str, cards: &CardMap) -> DeckResolveResult {
-    let re =
-        Regex::new(r"^(\d+)x\s+(.+?)(?:\s+\(([^)]+)\)\s+(\S+)(?:\s+\*[^*]*\*)?)?(?:\s+\[([^\]]+)\])?$").unwrap();
+impl CardReference<'_> {
+    fn resolve(self, cards: &CardMap) -> Option<&Card> {
+        match self {
+            CardReference::Scryfall(id) => cards.get_by_scryfall_id(id),
+            CardReference::Oracle(id) => cards.get_by_oracle_id(id),
+            CardReference::Mtgo(id) => cards.get_by_mtgo_id(id),
+            CardReference::Arena(id) => cards.get_by_arena_id(id),
+        }
+    }
+}
 
-    let mut entries = Vec::new();
-    let mut errors = Vec::new();
-    let mut total_cards = 0;
+/// How many "did you mean" suggestions to offer for an unresolved card name.
+const MAX_SUGGESTIONS: usize = 5;
 
-    for (line_number, line) in input.lines().enumerate() {
-        let line = line.trim();
+/// What a deck line or batch lookup asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardQuery<'a> {
+    /// A card by one of its IDs
+    Reference(CardReference<'a>),
+    /// A card by name, optionally in a specific set or printing
+    Name {
+        name: &'a str,
+        set_code: Option<&'a str>,
+        collector_number: Option<&'a str>,
+    },
+    /// A printing by set code and collector number alone
+    Printing {
+        set_code: &'a str,
+        collector_number: &'a str,
+    },
+}
 
-        // Skip empty lines and comments
-        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
-            continue;
+impl<'a> CardQuery<'a> {
+    /// The query for a name as written in a deck line, which may be an ID reference.
+    pub fn from_name(
+        name: &'a str,
+        set_code: Option<&'a str>,
+        collector_number: Option<&'a str>,
+    ) -> Self {
+        match CardReference::parse(name) {
+            Some(reference) => CardQuery::Reference(reference),
+            None => CardQuery::Name {
+                name,
+                set_code,
+                collector_number,
+            },
         }
+    }
 
-        match re.captures(line) {
-            Some(caps) => {
-                let quantity = caps.get(1).unwrap().as_str().parse::<u32>().unwrap_or(0);
-                let name = caps.get(2).unwrap().as_str().trim().to_string();
-                let categories = caps
-                    .get(5)
-                    .map(|m| {
-                        m.as_str()
-                            .split(',')
-                            .map(|c| c.trim().to_string())
-                            .collect()
-                    })
-                    .unwrap_or_else(Vec::new);
+    /// Finds the card, preferring the exact printing when one was given and otherwise the
+    /// printing chosen by `preference`.
+    ///
+    /// Returns the card with a warning when the lookup had to fall back to another printing, or
+    /// the closest card names when nothing matched.
+    pub fn resolve<'c>(
+        self,
+        cards: &'c CardMap,
+        preference: &PrintingPreference,
+    ) -> Result<(&'c Card, Option<ParseErrorKind>), Vec<String>> {
+        match self {
+            // An ID already names a printing, so a set and collector number are ignored
+            CardQuery::Reference(reference) => reference
+                .resolve(cards)
+                .map(|card| (card, None))
+                .ok_or_else(Vec::new),
+            CardQuery::Name {
+                name,
+                set_code,
+                collector_number,
+            } => resolve_card(cards, name, set_code, collector_number, preference),
+            CardQuery::Printing {
+                set_code,
+                collector_number,
+            } => get_card_by_printing(cards, set_code, collector_number)
+                .map(|card| (card, None))
+                .ok_or_else(Vec::new),
+        }
+    }
+}
 
-                if quantity == 0 {
-                    errors.push(ParseError {
-                        line_number: line_number + 1,
-                        line: line.to_string(),
-                        error: "Invalid quantity".to_string(),
-                    });
-                    continue;
-                }
+fn resolve_card<'a>(
+    cards: &'a CardMap,
+    name: &str,
+    set_code: Option<&str>,
+    collector_number: Option<&str>,
+    preference: &PrintingPreference,
+) -> Result<(&'a Card, Option<ParseErrorKind>), Vec<String>> {
+    let by_name = preference.choose(cards.printings(name));
 
-                if name.is_empty() {
-                    errors.push(ParseError {
-                        line_number: line_number + 1,
-                        line: line.to_string(),
-                        error: "Empty card name".to_string(),
-                    });
-                    continue;
-                }
+    let (Some(set_code), Some(collector_number)) = (set_code, collector_number) else {
+        // Without a collector number, the preferred printing in the set is used
+        let in_set = set_code.and_then(|set_code| {
+            preference.choose(
+                cards
+                    .printings(name)
+                    .filter(|card| card.set.eq_ignore_ascii_case(set_code)),
+            )
+        });
+        return match (in_set, by_name) {
+            (Some(card), _) => Ok((card, None)),
+            (None, Some(card)) => Ok((
+                card,
+                set_code.map(|set_code| {
+                    let set_code = set_code.to_string();
+                    let name = name.to_string();
+                    if cards.sets().get(&set_code).is_none() {
+                        ParseErrorKind::UnknownSet { set_code, name }
+                    } else {
+                        ParseErrorKind::NotInSet { set_code, name }
+                    }
+                }),
+            )),
+            (None, None) => Err(cards.suggest_names(name, MAX_SUGGESTIONS)),
+        };
+    };
 
-                let Some(card) = get_card_by_name(cards, &name).cloned() else {
-                    errors.push(ParseError {
-                        line_number: line_number + 1,
-                        line: line.to_string(),
-                        error: format!("Card not found: {}", name),
-                    });
-                    continue;
-                };
+    match (
+        get_card_by_printing(cards, set_code, collector_number),
+        by_name,
+    ) {
+        (Some(printing), Some(card)) if printing.name == card.name => Ok((printing, None)),
+        // The printing is all there is to go on, as with a misspelled name
+        (Some(printing), None) => Ok((
+            printing,
+            Some(ParseErrorKind::NameMismatch {
+                set_code: set_code.to_string(),
+                collector_number: collector_number.to_string(),
+                printing_name: printing.name.to_string(),
+                name: name.to_string(),
+            }),
+        )),
+        (Some(printing), Some(card)) => Ok((
+            card,
+            Some(ParseErrorKind::PrintingMismatch {
+                set_code: set_code.to_string(),
+                collector_number: collector_number.to_string(),
+                printing_name: printing.name.to_string(),
+                name: name.to_string(),
+            }),
+        )),
+        (None, Some(card)) if cards.sets().get(set_code).is_none() => Ok((
+            card,
+            Some(ParseErrorKind::UnknownSet {
+                set_code: set_code.to_string(),
+                name: name.to_string(),
+            }),
+        )),
+        (None, Some(card)) => Ok((
+            card,
+            Some(ParseErrorKind::PrintingNotFound {
+                set_code: set_code.to_string(),
+                collector_number: collector_number.to_string(),
+                name: name.to_string(),
+            }),
+        )),
+        (None, None) => Err(cards.suggest_names(name, MAX_SUGGESTIONS)),
+    }
+}
+
+/// Resolves a deck list in the given format, using `preference` to pick printings for lines
+/// without a set.
+pub fn resolve_deck_list<'a>(
+    input: &str,
+    format: DeckFormat,
+    cards: &'a CardMap,
+    preference: &PrintingPreference,
+) -> DeckResolveResult<'a> {
+    resolve_parsed(format.parse(input), cards, preference)
+}
 
-                total_cards += quantity;
-                entries.push(DeckEntry {
-                    categories,
-                    card,
-                    quantity,
+/// Looks up the cards of parsed deck lines. Lines whose card is not found are kept, with a
+/// warning.
+fn resolve_parsed<'a>(
+    parsed: ParsedDeckList,
+    cards: &'a CardMap,
+    preference: &PrintingPreference,
+) -> DeckResolveResult<'a> {
+    let ParsedDeckList {
+        name: deck_name,
+        lines,
+        errors,
+    } = parsed;
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    let mut total_cards = 0;
+    let mut sections = BTreeMap::new();
+
+    for line in &lines {
+        let name: &str = &line.name.value;
+        let set_code = line.set_code.map(|set_code| set_code.value.to_lowercase());
+        // An art variant stands for the collector number of that printing
+        let variant_number =
+            line.variant
+                .zip(set_code.as_deref())
+                .and_then(|(variant, set_code)| {
+                    let index = (variant.value as usize).checked_sub(1)?;
+                    let printings = cards.printings_in_set(name, set_code);
+                    printings
+                        .get(index)
+                        .map(|card| card.collector_number.as_str())
                 });
+        let collector_number = line
+            .collector_number
+            .map(|number| number.value)
+            .or(variant_number);
+        let number_span = line
+            .collector_number
+            .map(|number| number.span)
+            .or(line.variant.map(|variant| variant.span));
+        let warn = |span: Span, kind: ParseErrorKind| {
+            ParseError::new(line.line_number, line.line, span, kind)
+        };
+
+        // Unresolved cards are kept so the client can still show what was asked for
+        // An ID from the file is used when it is known, and the name otherwise
+        let query = line
+            .reference
+            .filter(|reference| reference.resolve(cards).is_some())
+            .map(CardQuery::Reference)
+            .unwrap_or_else(|| CardQuery::from_name(name, set_code.as_deref(), collector_number));
+        let card = match query.resolve(cards, preference) {
+            Ok((card, warning)) => {
+                if let Some(warning) = warning {
+                    // Printing problems point at the set code and collector number
+                    let span = match (&warning, line.set_code, number_span) {
+                        (ParseErrorKind::NameMismatch { .. }, _, _) => line.name.span,
+                        (
+                            ParseErrorKind::UnknownSet { .. } | ParseErrorKind::NotInSet { .. },
+                            Some(set_code),
+                            _,
+                        ) => set_code.span,
+                        (_, Some(set_code), Some(number_span)) => set_code.span.to(number_span),
+                        _ => line.name.span,
+                    };
+                    warnings.push(warn(span, warning));
+                }
+                Some(card)
             }
-            None => {
-                errors.push(ParseError {
-                    line_number: line_number + 1,
-                    line: line.to_string(),
-                    error: "Failed to parse deck entry format".to_string(),
+            Err(suggestions) => {
+                warnings.push(ParseError {
+                    suggestions,
+                    ..warn(
+                        line.name.span,
+                        ParseErrorKind::CardNotFound {
+                            name: name.to_string(),
+                        },
+                    )
                 });
+                None
             }
-        }
+        };
+
+        // MTGO has separate IDs for foil printings
+        let finish = line.finish().or(match (query, card) {
+            (CardQuery::Reference(CardReference::Mtgo(id)), Some(card))
+                if card.mtgo_foil_id == Some(id) =>
+            {
+                Some(Finish::Foil)
+            }
+            _ => None,
+        });
+
+        total_cards += line.quantity.value;
+        *sections.entry(line.section).or_insert(0) += line.quantity.value;
+        entries.push(DeckEntry {
+            name: name.to_string(),
+            quantity: line.quantity.value,
+            set_code,
+            collector_number: collector_number.map(str::to_string),
+            finish,
+            categories: line
+                .categories
+                .iter()
+                .map(|category| category.value.to_string())
+                .collect(),
+            section: line.section,
+            card,
+            set: card.and_then(|card| cards.sets().get(&card.set)),
+        });
     }
 
     DeckResolveResult {
+        name: deck_name.map(Cow::into_owned),
         entries,
         total_cards,
+        sections,
         errors,
+        warnings,
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeckDiffEntry {
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckDiffEntry<'a> {
     pub card_name: String,
     pub old_quantity: u32,
     pub new_quantity: u32,
-    pub change_type: String, // "added", "removed", "modified", "unchanged"
-    pub card: Option<Card>,
+    pub change_type: String, // "added", "removed", "modified", "unchanged", "moved"
+    /// The section the change is in; for moves, the section the cards moved to
+    pub section: Section,
+    /// For moves, the section the cards moved from
+    pub previous_section: Option<Section>,
+    pub set_code: Option<String>,
+    pub collector_number: Option<String>,
+    pub finish: Option<Finish>,
+    pub card: Option<&'a Card>,
+    pub set: Option<&'a CardSet>,
     pub categories: Vec<String>,
 }
 
+impl<'a> DeckDiffEntry<'a> {
+    fn new(change_type: &str, old_quantity: u32, new_quantity: u32, entry: &DeckEntry<'a>) -> Self {
+        Self {
+            card_name: entry.card_name().to_string(),
+            old_quantity,
+            new_quantity,
+            change_type: change_type.to_string(),
+            section: entry.section,
+            previous_section: None,
+            set_code: entry.set_code.clone(),
+            collector_number: entry.collector_number.clone(),
+            finish: entry.finish,
+            card: entry.card,
+            set: entry.set,
+            categories: entry.categories.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeckDiffRequest {
     pub deck_list_1: String,
     pub deck_list_2: String,
+    #[serde(default)]
+    pub format_1: DeckFormat,
+    #[serde(default)]
+    pub format_2: DeckFormat,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeckDiffResult {
-    pub added: Vec<DeckDiffEntry>,
-    pub removed: Vec<DeckDiffEntry>,
-    pub modified: Vec<DeckDiffEntry>,
-    pub unchanged: Vec<DeckDiffEntry>,
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckDiffResult<'a> {
+    pub added: Vec<DeckDiffEntry<'a>>,
+    pub removed: Vec<DeckDiffEntry<'a>>,
+    pub modified: Vec<DeckDiffEntry<'a>>,
+    pub unchanged: Vec<DeckDiffEntry<'a>>,
+    /// Copies of a card that moved between sections, such as from the sideboard to the main deck
+    pub moved: Vec<DeckDiffEntry<'a>>,
     pub errors_deck_1: Vec<ParseError>,
     pub errors_deck_2: Vec<ParseError>,
+    pub warnings_deck_1: Vec<ParseError>,
+    pub warnings_deck_2: Vec<ParseError>,
 }
 
-pub fn diff_decks(deck1_input: &str, deck2_input: &str, cards: &CardMap) -> DeckDiffResult {
-    let deck1_result = resolve_deck_list(deck1_input, cards);
-    let deck2_result = resolve_deck_list(deck2_input, cards);
-
-    // Create maps for easier comparison
-    let mut deck1_map = std::collections::HashMap::new();
-    for entry in &deck1_result.entries {
-        deck1_map.insert(entry.card.name.clone(), entry);
+/// The total quantity of each card in each section, with the first entry for it.
+fn group_entries<'e, 'a>(
+    entries: &'e [DeckEntry<'a>],
+) -> BTreeMap<(&'e str, Section), (u32, &'e DeckEntry<'a>)> {
+    let mut groups = BTreeMap::new();
+    for entry in entries {
+        groups
+            .entry((entry.card_name(), entry.section))
+            .or_insert((0, entry))
+            .0 += entry.quantity;
     }
+    groups
+}
 
-    let mut deck2_map = std::collections::HashMap::new();
-    for entry in &deck2_result.entries {
-        deck2_map.insert(entry.card.name.clone(), entry);
-    }
+/// Compares two resolved deck lists.
+///
+/// Cards are compared per section. When a card has fewer copies in one section and more in
+/// another, the difference is reported as moved and left out of the other change kinds.
+pub fn diff_decks<'a>(
+    deck1_result: DeckResolveResult<'a>,
+    deck2_result: DeckResolveResult<'a>,
+) -> DeckDiffResult<'a> {
+    let deck1_groups = group_entries(&deck1_result.entries);
+    let deck2_groups = group_entries(&deck2_result.entries);
 
     let mut added = Vec::new();
     let mut removed = Vec::new();
     let mut modified = Vec::new();
     let mut unchanged = Vec::new();
+    let mut moved = Vec::new();
 
-    // Find all unique card names
-    let all_cards: std::collections::HashSet<String> = deck1_map
-        .keys()
-        .chain(deck2_map.keys())
-        .cloned()
-        .collect();
-
-    for card_name in all_cards {
-        let deck1_entry = deck1_map.get(&card_name);
-        let deck2_entry = deck2_map.get(&card_name);
-
-        match (deck1_entry, deck2_entry) {
-            (None, Some(entry2)) => {
-                // Card added in deck 2
-                added.push(DeckDiffEntry {
-                    card_name: card_name.clone(),
-                    old_quantity: 0,
-                    new_quantity: entry2.quantity,
-                    change_type: "added".to_string(),
-                    card: Some(entry2.card.clone()),
-                    categories: entry2.categories.clone(),
-                });
-            }
-            (Some(entry1), None) => {
-                // Card removed from deck 2
-                removed.push(DeckDiffEntry {
-                    card_name: card_name.clone(),
-                    old_quantity: entry1.quantity,
-                    new_quantity: 0,
-                    change_type: "removed".to_string(),
-                    card: Some(entry1.card.clone()),
-                    categories: entry1.categories.clone(),
+    // Sections each card appears in, in either deck
+    let mut card_sections: BTreeMap<&str, BTreeSet<Section>> = BTreeMap::new();
+    for &(card_name, section) in deck1_groups.keys().chain(deck2_groups.keys()) {
+        card_sections.entry(card_name).or_default().insert(section);
+    }
+
+    for (card_name, sections) in card_sections {
+        let quantities: Vec<(Section, u32, u32)> = sections
+            .into_iter()
+            .map(|section| {
+                let quantity = |groups: &BTreeMap<_, (u32, _)>| {
+                    groups.get(&(card_name, section)).map_or(0, |group| group.0)
+                };
+                (section, quantity(&deck1_groups), quantity(&deck2_groups))
+            })
+            .collect();
+
+        // Pair up sections that lost copies with sections that gained them
+        let mut moved_out = vec![0; quantities.len()];
+        let mut moved_in = vec![0; quantities.len()];
+        for from in 0..quantities.len() {
+            for to in 0..quantities.len() {
+                let (_, old_from, new_from) = quantities[from];
+                let (_, old_to, new_to) = quantities[to];
+                let lost = old_from.saturating_sub(new_from) - moved_out[from];
+                let gained = new_to.saturating_sub(old_to) - moved_in[to];
+                let quantity = lost.min(gained);
+                if quantity == 0 {
+                    continue;
+                }
+                moved_out[from] += quantity;
+                moved_in[to] += quantity;
+
+                let (_, entry) = deck2_groups[&(card_name, quantities[to].0)];
+                moved.push(DeckDiffEntry {
+                    previous_section: Some(quantities[from].0),
+                    ..DeckDiffEntry::new("moved", quantity, quantity, entry)
                 });
             }
-            (Some(entry1), Some(entry2)) => {
-                if entry1.quantity != entry2.quantity {
-                    // Quantity changed
-                    modified.push(DeckDiffEntry {
-                        card_name: card_name.clone(),
-                        old_quantity: entry1.quantity,
-                        new_quantity: entry2.quantity,
-                        change_type: "modified".to_string(),
-                        card: Some(entry2.card.clone()),
-                        categories: entry2.categories.clone(),
-                    });
-                } else {
-                    // No change
-                    unchanged.push(DeckDiffEntry {
-                        card_name: card_name.clone(),
-                        old_quantity: entry1.quantity,
-                        new_quantity: entry2.quantity,
-                        change_type: "unchanged".to_string(),
-                        card: Some(entry1.card.clone()),
-                        categories: entry1.categories.clone(),
-                    });
+        }
+
+        for (index, &(section, old_quantity, new_quantity)) in quantities.iter().enumerate() {
+            let old_quantity = old_quantity - moved_out[index];
+            let new_quantity = new_quantity - moved_in[index];
+            let old_entry = deck1_groups.get(&(card_name, section)).map(|group| group.1);
+            let new_entry = deck2_groups.get(&(card_name, section)).map(|group| group.1);
+
+            match (old_quantity, new_quantity, old_entry, new_entry) {
+                (0, 0, _, _) => {}
+                (0, _, _, Some(entry)) => {
+                    added.push(DeckDiffEntry::new("added", 0, new_quantity, entry))
+                }
+                (_, 0, Some(entry), _) => {
+                    removed.push(DeckDiffEntry::new("removed", old_quantity, 0, entry))
+                }
+                (_, _, Some(old_entry), Some(new_entry)) => {
+                    if old_quantity != new_quantity {
+                        modified.push(DeckDiffEntry::new(
+                            "modified",
+                            old_quantity,
+                            new_quantity,
+                            new_entry,
+                        ));
+                    } else {
+                        unchanged.push(DeckDiffEntry::new(
+                            "unchanged",
+                            old_quantity,
+                            new_quantity,
+                            old_entry,
+                        ));
+                    }
+                }
+                _ => {
+                    // A non-zero quantity always has an entry
                 }
-            }
-            (None, None) => {
-                // This shouldn't happen given our logic above
             }
         }
     }
 
-    // Sort entries by card name for consistent output
-    added.sort_by(|a, b| a.card_name.cmp(&b.card_name));
-    removed.sort_by(|a, b| a.card_name.cmp(&b.card_name));
-    modified.sort_by(|a, b| a.card_name.cmp(&b.card_name));
-    unchanged.sort_by(|a, b| a.card_name.cmp(&b.card_name));
-
     DeckDiffResult {
         added,
         removed,
         modified,
         unchanged,
+        moved,
         errors_deck_1: deck1_result.errors,
         errors_deck_2: deck2_result.errors,
+        warnings_deck_1: deck1_result.warnings,
+        warnings_deck_2: deck2_result.warnings,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cards::{Card, ImageUris};
-    use std::{collections::HashMap, sync::Arc};
+    use crate::cards::{Card, CardDatabase, CardFace, ImageUris};
+    use crate::deck_list::Severity;
+    use std::sync::Arc;
 
     fn create_test_card(name: &str) -> Card {
         Card {
             id: format!("test-{}", name.replace(' ', "-").to_lowercase()),
-            name: name.to_string(),
-            image_status: "highres_scan".to_string(),
+            name: name.into(),
+            image_status: "highres_scan".into(),
             image_uris: Some(ImageUris {
                 normal: "test-url".to_string(),
+                ..Default::default()
             }),
+            ..Default::default()
         }
     }
 
     #[test]
     fn test_resolve_basic_deck_entry() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let input = "1x Lightning Bolt";
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.errors.len(), 0);
         assert_eq!(result.entries.len(), 1);
@@ -268,9 +565,14 @@ mod tests {
 
     #[test]
     fn test_resolve_full_deck_entry() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let input = "2x Blasphemous Act (eoc) 86 [Removal]";
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.errors.len(), 0);
         assert_eq!(result.entries.len(), 1);
@@ -285,15 +587,46 @@ mod tests {
         assert_eq!(entry.card, None); // Card not found
     }
 
+    #[test]
+    fn test_resolve_finish_marker() {
+        let cards = Arc::new(CardDatabase::default());
+        let input = r#"
+1x Sol Ring (c21) 263 *F* [Ramp]
+1x Arcane Signet (eoc) 53 *E*
+1x Command Tower (eoc) 59
+1x Forest (bfz) 251 *X*
+        "#;
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(result.entries[0].finish, Some(Finish::Foil));
+        assert_eq!(result.entries[0].categories, vec!["Ramp".to_string()]);
+        assert_eq!(result.entries[1].finish, Some(Finish::Etched));
+        assert_eq!(result.entries[2].finish, None);
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].line_number, 5);
+    }
+
     #[test]
     fn test_resolve_multiple_entries() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let input = r#"
 1x Lightning Bolt
 2x Counterspell (lea) 55 [Control]
 1x Forest [Land]
         "#;
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.errors.len(), 0);
         assert_eq!(result.entries.len(), 3);
@@ -302,7 +635,7 @@ mod tests {
 
     #[test]
     fn test_resolve_with_comments_and_empty_lines() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let input = r#"
 # This is a comment
 1x Lightning Bolt
@@ -310,7 +643,12 @@ mod tests {
 // Another comment
 2x Counterspell
         "#;
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.errors.len(), 0);
         assert_eq!(result.entries.len(), 2);
@@ -318,31 +656,103 @@ mod tests {
 
     #[test]
     fn test_resolve_invalid_entries() {
-        let cards = Arc::new(HashMap::new());
+        let cards = Arc::new(CardDatabase::default());
         let input = r#"
 0x Invalid Quantity
 xInvalid Format
 1x
         "#;
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.errors.len(), 3);
         assert_eq!(result.entries.len(), 0);
     }
 
+    #[test]
+    fn test_parse_errors_have_codes_and_spans() {
+        let cards = Arc::new(CardDatabase::new(vec![Card {
+            set: "tla".into(),
+            collector_number: "286".to_string(),
+            ..create_test_card("Forest")
+        }]));
+        let input = "0x Forest\n1x Forest (tla) 286 *X*\n  1x Fórest Elf\n1x Forest (tla) 999";
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        let errors: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| (&error.kind, error.severity, error.span))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    &ParseErrorKind::InvalidQuantity,
+                    Severity::Error,
+                    Span { start: 0, end: 1 }
+                ),
+                (
+                    &ParseErrorKind::UnknownFinish {
+                        marker: "X".to_string()
+                    },
+                    Severity::Error,
+                    Span { start: 20, end: 23 }
+                ),
+            ]
+        );
+
+        // Columns count characters, not bytes, of the trimmed line
+        let not_found = &result.warnings[0];
+        assert_eq!(not_found.severity, Severity::Warning);
+        assert_eq!(not_found.span, Span { start: 3, end: 13 });
+        assert_eq!(
+            sonic_rs::to_value(not_found).unwrap(),
+            sonic_rs::json!({
+                "line_number": 3,
+                "line": "1x Fórest Elf",
+                "severity": "warning",
+                "span": {"start": 3, "end": 13},
+                "code": "card_not_found",
+                "details": {"name": "Fórest Elf"},
+                "error": "Card not found: Fórest Elf",
+                "suggestions": ["Forest"]
+            })
+        );
+
+        let printing = &result.warnings[1];
+        assert_eq!(printing.span, Span { start: 11, end: 19 });
+        assert!(matches!(
+            &printing.kind,
+            ParseErrorKind::PrintingNotFound { collector_number, .. } if collector_number == "999"
+        ));
+    }
+
     #[test]
     fn test_resolve_with_card_resolution() {
-        let mut card_map = HashMap::new();
         let bolt_card = create_test_card("Lightning Bolt");
-        card_map.insert("Lightning Bolt".to_string(), vec![bolt_card.clone()]);
-        let cards = Arc::new(card_map);
+        let cards = Arc::new(CardDatabase::new(vec![bolt_card]));
 
         let input = r#"
 1x Lightning Bolt
 1x Nonexistent Card
         "#;
 
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.entries.len(), 2);
         assert_eq!(result.errors.len(), 0);
@@ -362,13 +772,10 @@ xInvalid Format
 
     #[test]
     fn test_resolve_multiple_categories() {
-        let mut card_map = HashMap::new();
-        card_map.insert(
-            "Lightning Bolt".to_string(),
-            vec![create_test_card("Lightning Bolt")],
-        );
-        card_map.insert("Forest".to_string(), vec![create_test_card("Forest")]);
-        let cards = Arc::new(card_map);
+        let cards = Arc::new(CardDatabase::new(vec![
+            create_test_card("Lightning Bolt"),
+            create_test_card("Forest"),
+        ]));
 
         let input = r#"
 1x Lightning Bolt [Removal, Burn, Instant]
@@ -376,7 +783,12 @@ xInvalid Format
 1x Sol Ring [Artifact, Ramp]
         "#;
 
-        let result = resolve_deck_list(input, &cards);
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
 
         assert_eq!(result.entries.len(), 3);
         assert_eq!(result.errors.len(), 0);
@@ -400,4 +812,305 @@ xInvalid Format
         assert_eq!(result.entries[2].categories[1], "Ramp");
         assert!(result.entries[2].card.is_none()); // Sol Ring not found
     }
+
+    #[test]
+    fn test_resolve_specific_printing() {
+        let printing = |name: &str, set: &str, collector_number: &str| Card {
+            set: set.into(),
+            collector_number: collector_number.to_string(),
+            id: format!("{}-{}", set, collector_number),
+            ..create_test_card(name)
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            printing("Forest", "tla", "286"),
+            printing("Forest", "bfz", "251"),
+            printing("Island", "bfz", "252"),
+        ]));
+
+        let input = r#"
+1x Forest (tla) 286
+1x Forest (bfz) 251
+1x Forest (bfz) 999
+1x Forest (bfz) 252
+1x Forest
+1x Forst (bfz) 252
+1x Island (tla)
+        "#;
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.errors.len(), 0);
+        let ids: Vec<_> = result
+            .entries
+            .iter()
+            .map(|entry| entry.card.as_ref().unwrap().id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "tla-286", "bfz-251", "tla-286", "tla-286", "tla-286", "bfz-252", "bfz-252"
+            ]
+        );
+
+        // Missing printing and mismatched printing both fall back to the name lookup
+        let warnings: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|warning| (warning.line_number, warning.error.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    4,
+                    "Printing not found: (bfz) 999; using default printing of Forest"
+                ),
+                (
+                    5,
+                    "Printing (bfz) 252 is Island, not Forest; using default printing"
+                ),
+                // An unknown name falls back to the printing
+                (7, "Card not found: Forst; using printing (bfz) 252, Island"),
+                (8, "Island is not in (tla); using default printing"),
+            ]
+        );
+        assert_eq!(result.warnings[2].span, Span { start: 3, end: 8 });
+        assert_eq!(result.warnings[3].span, Span { start: 11, end: 14 });
+    }
+
+    #[test]
+    fn test_resolve_warns_about_unknown_sets() {
+        let cards = Arc::new(
+            CardDatabase::new(vec![Card {
+                set: "tla".into(),
+                collector_number: "286".to_string(),
+                ..create_test_card("Forest")
+            }])
+            .with_sets(vec![CardSet {
+                code: "tla".into(),
+                name: "Avatar: The Last Airbender".into(),
+                set_type: "expansion".into(),
+                released_at: Some("2025-11-21".into()),
+                ..Default::default()
+            }]),
+        );
+
+        let input = "1x Forest (TLA) 286\n1x Forest (xyz) 1\n1x Forest (xyz)";
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.entries.len(), 3);
+        let set = result.entries[0].set.unwrap();
+        assert_eq!(set.name, "Avatar: The Last Airbender");
+        assert_eq!(set.released_at.as_deref(), Some("2025-11-21"));
+
+        assert_eq!(result.warnings.len(), 2);
+        assert_eq!(
+            result.warnings[0].error,
+            "Unknown set: (xyz); using default printing of Forest"
+        );
+        assert_eq!(result.warnings[1].line_number, 3);
+    }
+
+    #[test]
+    fn test_resolve_multi_faced_card_by_face_name() {
+        let aang = Card {
+            set: "tla".into(),
+            collector_number: "203".to_string(),
+            card_faces: Some(vec![
+                CardFace {
+                    name: "Aang, at the Crossroads".into(),
+                    ..Default::default()
+                },
+                CardFace {
+                    name: "Aang, Destined Savior".into(),
+                    ..Default::default()
+                },
+            ]),
+            ..create_test_card("Aang, at the Crossroads // Aang, Destined Savior")
+        };
+        let cards = Arc::new(CardDatabase::new(vec![aang]));
+
+        let input = r#"
+1x Aang, at the Crossroads // Aang, Destined Savior (tla) 203 [Draw]
+1x Aang, at the Crossroads (tla) 203
+1x Aang, Destined Savior
+        "#;
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.errors.len(), 0);
+        assert_eq!(result.warnings.len(), 0);
+        assert_eq!(result.entries.len(), 3);
+        for entry in &result.entries {
+            assert_eq!(
+                entry.card_name(),
+                "Aang, at the Crossroads // Aang, Destined Savior"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_unknown_card_suggests_names() {
+        let cards = Arc::new(CardDatabase::new(vec![
+            create_test_card("Lightning Bolt"),
+            create_test_card("Lightning Helix"),
+            create_test_card("Counterspell"),
+        ]));
+
+        let input = "1x Lightnig Bolt\n1x Zzyzx";
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.warnings.len(), 2);
+        assert_eq!(result.warnings[0].error, "Card not found: Lightnig Bolt");
+        assert_eq!(result.warnings[0].suggestions[0], "Lightning Bolt");
+        assert!(result.warnings[1].suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_cards_by_id() {
+        let bolt = Card {
+            id: "77c6fa74-5543-42ac-9ead-0e890b188e99".to_string(),
+            oracle_id: Some("4457ed35-7c10-48c8-9776-456485fdf070".into()),
+            mtgo_id: Some(100),
+            mtgo_foil_id: Some(101),
+            arena_id: Some(200),
+            ..create_test_card("Lightning Bolt")
+        };
+        let cards = Arc::new(CardDatabase::new(vec![bolt]));
+
+        let input = r#"
+1x scryfall:77c6fa74-5543-42ac-9ead-0e890b188e99
+1x oracle:4457ed35-7c10-48c8-9776-456485fdf070
+1x mtgo:100
+1x MTGO:101
+1x arena:200 [Burn]
+1x arena:999
+        "#;
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        assert_eq!(result.errors.len(), 0);
+        assert_eq!(result.entries.len(), 6);
+        for entry in &result.entries[..5] {
+            assert_eq!(entry.card_name(), "Lightning Bolt");
+        }
+        assert_eq!(result.entries[2].finish, None);
+        assert_eq!(result.entries[3].finish, Some(Finish::Foil));
+        assert_eq!(result.entries[4].categories, vec!["Burn"]);
+        assert!(result.entries[5].card.is_none());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].error, "Card not found: arena:999");
+    }
+
+    #[test]
+    fn test_resolve_applies_printing_preference() {
+        let printing = |set: &str, released_at: &str, promo: bool| Card {
+            set: set.into(),
+            collector_number: "1".to_string(),
+            released_at: released_at.into(),
+            promo,
+            ..create_test_card("Sol Ring")
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            printing("lea", "1993-08-05", false),
+            printing("c21", "2021-04-23", false),
+            printing("p30a", "2022-09-02", true),
+        ]));
+        let input = "1x Sol Ring\n1x Sol Ring (lea) 1";
+
+        let preference = PrintingPreference {
+            non_promo: true,
+            ..Default::default()
+        };
+        let result = resolve_deck_list(input, DeckFormat::Text, &cards, &preference);
+        assert_eq!(result.entries[0].card.unwrap().set, "c21");
+        // An explicit printing wins over the preference
+        assert_eq!(result.entries[1].card.unwrap().set, "lea");
+
+        let result = resolve_deck_list(
+            input,
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+        assert_eq!(result.entries[0].card.unwrap().set, "p30a");
+    }
+
+    #[test]
+    fn test_diff_reports_moves_between_sections() {
+        let cards = Arc::new(CardDatabase::default());
+        let deck1 = "Deck\n4 Opt\n2 Negate\n1 Duress\nSideboard\n1 Pyroblast";
+        let deck2 = "Deck\n3 Opt\n1 Negate\n1 Pyroblast\nSideboard\n1 Opt\n2 Negate\n1 Duress";
+        let resolve = |input| {
+            resolve_deck_list(
+                input,
+                DeckFormat::Text,
+                &cards,
+                &PrintingPreference::default(),
+            )
+        };
+        let result = diff_decks(resolve(deck1), resolve(deck2));
+
+        let summary = |entries: &[DeckDiffEntry]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{} {:?}->{:?} {}/{}",
+                        entry.card_name,
+                        entry.previous_section,
+                        entry.section,
+                        entry.old_quantity,
+                        entry.new_quantity
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(
+            summary(&result.moved),
+            vec![
+                "Duress Some(Main)->Sideboard 1/1",
+                "Negate Some(Main)->Sideboard 1/1",
+                "Opt Some(Main)->Sideboard 1/1",
+                "Pyroblast Some(Sideboard)->Main 1/1",
+            ]
+        );
+        assert_eq!(summary(&result.added), vec!["Negate None->Sideboard 0/1"]);
+        assert!(result.removed.is_empty());
+        assert!(result.modified.is_empty());
+        assert_eq!(
+            summary(&result.unchanged),
+            vec!["Negate None->Main 1/1", "Opt None->Main 3/3"]
+        );
+
+        let deck2 = resolve(deck2);
+        assert_eq!(
+            deck2.sections,
+            BTreeMap::from([(Section::Main, 5), (Section::Sideboard, 4)])
+        );
+        assert_eq!(deck2.total_cards, 9);
+    }
 }
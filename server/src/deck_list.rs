@@ -0,0 +1,807 @@
+//! Parsing deck lists, without looking up any cards.
+//!
+//! A deck line is `{quantity}[x] {name} [({set}) [{collector number}]] [*{finish}*...]
+//! [[{category}, ...]]`. Lines are split into tokens first: plain text, and groups in
+//! parentheses, square brackets or asterisks. The grammar then reads the quantity from the front
+//! and the categories, finish markers and printing from the back, and whatever is left is the
+//! name. Parenthesized text that is not a set code, as in `B.F.M. (Big Furry Monster)`, stays
+//! part of the name. Every part keeps its [`Span`], so problems can point at the exact columns.
+//!
+//! Header lines such as `Sideboard` or `// Commander` start a [`Section`]. Lists without headers
+//! are all in the main deck, however their lines are grouped.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Special finish requested for a printing, written as `*F*` or `*E*` after the collector number.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Finish {
+    Foil,
+    Etched,
+}
+
+impl Finish {
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker.trim() {
+            "F" | "f" => Some(Finish::Foil),
+            "E" | "e" => Some(Finish::Etched),
+            _ => None,
+        }
+    }
+}
+
+/// A card written by one of its IDs instead of its name: `scryfall:<uuid>`, `oracle:<uuid>`,
+/// `mtgo:<id>` or `arena:<id>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardReference<'a> {
+    Scryfall(&'a str),
+    Oracle(&'a str),
+    Mtgo(u32),
+    Arena(u32),
+}
+
+impl<'a> CardReference<'a> {
+    pub fn parse(name: &'a str) -> Option<Self> {
+        let (kind, id) = name.split_once(':')?;
+        let id = id.trim();
+        match kind.trim().to_lowercase().as_str() {
+            "scryfall" => Some(CardReference::Scryfall(id)),
+            "oracle" => Some(CardReference::Oracle(id)),
+            "mtgo" => id.parse().ok().map(CardReference::Mtgo),
+            "arena" => id.parse().ok().map(CardReference::Arena),
+            _ => None,
+        }
+    }
+}
+
+/// The part of a deck a card is in.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Commander,
+    Companion,
+    #[default]
+    Main,
+    Sideboard,
+    Maybeboard,
+}
+
+impl Section {
+    /// The section a header line starts, such as `Sideboard`, `// Commander` or `Deck:`.
+    pub fn from_header(line: &str) -> Option<Self> {
+        let header = line.strip_prefix("//").unwrap_or(line).trim();
+        let header = header.strip_suffix(':').unwrap_or(header).trim_end();
+        // Some exports count the cards in a section, as in `Sideboard (15)`
+        let header = match header
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once('('))
+        {
+            Some((name, count)) if count.trim().bytes().all(|b| b.is_ascii_digit()) => {
+                name.trim_end()
+            }
+            _ => header,
+        };
+        match header.to_lowercase().as_str() {
+            "commander" | "commanders" => Some(Section::Commander),
+            "companion" | "companions" => Some(Section::Companion),
+            "deck" | "main" | "mainboard" | "main deck" => Some(Section::Main),
+            "sideboard" | "side" => Some(Section::Sideboard),
+            "maybeboard" | "maybe" | "considering" => Some(Section::Maybeboard),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a problem kept a line out of the deck or only affected how it resolved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem with a deck line. Serialized as a stable snake_case `code`, with any details
+/// about the problem under `details`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "code", content = "details", rename_all = "snake_case")]
+pub enum ParseErrorKind {
+    /// The line does not start with a quantity such as `1x` or `1`
+    MissingQuantity,
+    InvalidQuantity,
+    EmptyCardName,
+    /// A `(`, `[` or `*` without its closing counterpart
+    Unterminated {
+        expected: char,
+    },
+    /// Text after the printing, or a group where the grammar does not allow one
+    UnexpectedText {
+        text: String,
+    },
+    UnknownFinish {
+        marker: String,
+    },
+    ConflictingFinishes,
+    /// A file format that could not be read, such as malformed XML
+    InvalidFile {
+        message: String,
+    },
+    CardNotFound {
+        name: String,
+    },
+    UnknownSet {
+        set_code: String,
+        name: String,
+    },
+    /// The set exists but has no printing of the card
+    NotInSet {
+        set_code: String,
+        name: String,
+    },
+    PrintingNotFound {
+        set_code: String,
+        collector_number: String,
+        name: String,
+    },
+    /// The printing exists but is a different card than the one named
+    PrintingMismatch {
+        set_code: String,
+        collector_number: String,
+        printing_name: String,
+        name: String,
+    },
+    /// No card has the name, but the printing exists and is used instead
+    NameMismatch {
+        set_code: String,
+        collector_number: String,
+        printing_name: String,
+        name: String,
+    },
+}
+
+impl ParseErrorKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ParseErrorKind::MissingQuantity
+            | ParseErrorKind::InvalidQuantity
+            | ParseErrorKind::EmptyCardName
+            | ParseErrorKind::Unterminated { .. }
+            | ParseErrorKind::UnexpectedText { .. }
+            | ParseErrorKind::UnknownFinish { .. }
+            | ParseErrorKind::ConflictingFinishes
+            | ParseErrorKind::InvalidFile { .. } => Severity::Error,
+            ParseErrorKind::CardNotFound { .. }
+            | ParseErrorKind::UnknownSet { .. }
+            | ParseErrorKind::NotInSet { .. }
+            | ParseErrorKind::PrintingNotFound { .. }
+            | ParseErrorKind::PrintingMismatch { .. }
+            | ParseErrorKind::NameMismatch { .. } => Severity::Warning,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::MissingQuantity => write!(f, "Expected a quantity, such as 1x"),
+            ParseErrorKind::InvalidQuantity => write!(f, "Invalid quantity"),
+            ParseErrorKind::EmptyCardName => write!(f, "Empty card name"),
+            ParseErrorKind::Unterminated { expected } => {
+                write!(f, "Missing closing '{}'", expected)
+            }
+            ParseErrorKind::UnexpectedText { text } => write!(f, "Unexpected text: {}", text),
+            ParseErrorKind::UnknownFinish { marker } => {
+                write!(f, "Unknown finish marker: *{}*", marker)
+            }
+            ParseErrorKind::ConflictingFinishes => write!(f, "Conflicting finish markers"),
+            ParseErrorKind::InvalidFile { message } => write!(f, "Invalid file: {}", message),
+            ParseErrorKind::CardNotFound { name } => write!(f, "Card not found: {}", name),
+            ParseErrorKind::UnknownSet { set_code, name } => write!(
+                f,
+                "Unknown set: ({}); using default printing of {}",
+                set_code, name
+            ),
+            ParseErrorKind::NotInSet { set_code, name } => write!(
+                f,
+                "{} is not in ({}); using default printing",
+                name, set_code
+            ),
+            ParseErrorKind::PrintingNotFound {
+                set_code,
+                collector_number,
+                name,
+            } => write!(
+                f,
+                "Printing not found: ({}) {}; using default printing of {}",
+                set_code, collector_number, name
+            ),
+            ParseErrorKind::PrintingMismatch {
+                set_code,
+                collector_number,
+                printing_name,
+                name,
+            } => write!(
+                f,
+                "Printing ({}) {} is {}, not {}; using default printing",
+                set_code, collector_number, printing_name, name
+            ),
+            ParseErrorKind::NameMismatch {
+                set_code,
+                collector_number,
+                printing_name,
+                name,
+            } => write!(
+                f,
+                "Card not found: {}; using printing ({}) {}, {}",
+                name, set_code, collector_number, printing_name
+            ),
+        }
+    }
+}
+
+/// A range of character columns in a line, starting at 0 and excluding `end`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The span of the byte range `start..end` of `line`.
+    pub(crate) fn from_bytes(line: &str, start: usize, end: usize) -> Self {
+        let start_column = line[..start].chars().count();
+        Self {
+            start: start_column,
+            end: start_column + line[start..end].chars().count(),
+        }
+    }
+
+    /// The span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseError {
+    pub line_number: usize,
+    /// The line with surrounding whitespace removed
+    pub line: String,
+    pub severity: Severity,
+    /// The part of `line` the problem is about
+    pub span: Span,
+    #[serde(flatten)]
+    pub kind: ParseErrorKind,
+    /// Human-readable description of `kind`
+    pub error: String,
+    /// Closest known card names when the error is about an unknown card, best match first
+    pub suggestions: Vec<String>,
+}
+
+impl ParseError {
+    pub fn new(line_number: usize, line: &str, span: Span, kind: ParseErrorKind) -> Self {
+        Self {
+            line_number,
+            line: line.to_string(),
+            severity: kind.severity(),
+            span,
+            error: kind.to_string(),
+            kind,
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+/// A value with the columns it was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// The same span with a different value, such as the parsed form of the text.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned {
+            value: f(self.value),
+            span: self.span,
+        }
+    }
+}
+
+/// A deck line split into its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckLine<'a> {
+    pub line_number: usize,
+    /// The line with surrounding whitespace removed, which the spans refer to
+    pub line: &'a str,
+    pub section: Section,
+    pub quantity: Spanned<u32>,
+    /// Owned when the format escapes names, as XML does
+    pub name: Spanned<Cow<'a, str>>,
+    /// The card's ID, for formats that store one. The name is used if the ID is not known.
+    pub reference: Option<CardReference<'a>>,
+    pub set_code: Option<Spanned<&'a str>>,
+    pub collector_number: Option<Spanned<&'a str>>,
+    /// Which of the card's printings in the set is meant, counting from 1 in collector number
+    /// order, for formats that number art variants instead of writing collector numbers
+    pub variant: Option<Spanned<u32>>,
+    /// Every finish marker, in the order written
+    pub finishes: Vec<Spanned<Finish>>,
+    pub categories: Vec<Spanned<&'a str>>,
+}
+
+impl DeckLine<'_> {
+    /// The finish of the entry. Lines with conflicting markers are rejected while parsing.
+    pub fn finish(&self) -> Option<Finish> {
+        self.finishes.first().map(|finish| finish.value)
+    }
+}
+
+/// The lines of a deck list that parsed, and errors for the ones that did not.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedDeckList<'a> {
+    /// The deck's name, for formats that store one
+    pub name: Option<Cow<'a, str>>,
+    pub lines: Vec<DeckLine<'a>>,
+    pub errors: Vec<ParseError>,
+}
+
+/// Parses every line of a deck list, skipping blank lines and `#` or `//` comments.
+pub fn parse_deck_list(input: &str) -> ParsedDeckList<'_> {
+    parse_lines(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line)),
+    )
+}
+
+/// Parses deck lines given with their line numbers, for formats that wrap deck lines in
+/// something else.
+pub fn parse_lines<'a>(lines: impl IntoIterator<Item = (usize, &'a str)>) -> ParsedDeckList<'a> {
+    parse_lines_with(lines, parse_line)
+}
+
+/// Like [`parse_lines`], with a different parser for the card lines of formats that extend the
+/// grammar.
+pub(crate) fn parse_lines_with<'a>(
+    lines: impl IntoIterator<Item = (usize, &'a str)>,
+    parse_line: impl Fn(usize, &'a str) -> Result<DeckLine<'a>, LineError>,
+) -> ParsedDeckList<'a> {
+    let mut parsed = ParsedDeckList::default();
+    let mut section = Section::Main;
+
+    for (line_number, line) in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = Section::from_header(line) {
+            section = header;
+            continue;
+        }
+        if line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        match parse_line(line_number, line) {
+            Ok(mut line) => {
+                // A commander written as a category, as in `1x Atraxa [Commander]`
+                line.section = if section == Section::Main
+                    && line
+                        .categories
+                        .iter()
+                        .any(|category| category.value.eq_ignore_ascii_case("commander"))
+                {
+                    Section::Commander
+                } else {
+                    section
+                };
+                parsed.lines.push(line);
+            }
+            Err((start, end, kind)) => parsed.errors.push(ParseError::new(
+                line_number,
+                line,
+                Span::from_bytes(line, start, end),
+                kind,
+            )),
+        }
+    }
+    parsed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Text,
+    Parens,
+    Brackets,
+    Stars,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    /// The token without its delimiters
+    text: &'a str,
+    /// Byte range of the whole token, including delimiters
+    start: usize,
+    end: usize,
+}
+
+impl Token<'_> {
+    /// Byte range of the token without its delimiters.
+    fn inner(&self) -> (usize, usize) {
+        match self.kind {
+            TokenKind::Text => (self.start, self.end),
+            _ => (self.start + 1, self.end - 1),
+        }
+    }
+}
+
+/// A problem with a line and the byte range of the line it is about.
+pub(crate) type LineError = (usize, usize, ParseErrorKind);
+
+fn tokenize(line: &str) -> Result<Vec<Token<'_>>, LineError> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let group = match bytes[pos] {
+            b'(' => Some((TokenKind::Parens, ')')),
+            b'[' => Some((TokenKind::Brackets, ']')),
+            b'*' => Some((TokenKind::Stars, '*')),
+            _ => None,
+        };
+        let token = match group {
+            Some((kind, closing)) => {
+                let Some(length) = line[pos + 1..].find(closing) else {
+                    return Err((
+                        pos,
+                        line.len(),
+                        ParseErrorKind::Unterminated { expected: closing },
+                    ));
+                };
+                let close = pos + 1 + length;
+                Token {
+                    kind,
+                    text: &line[pos + 1..close],
+                    start: pos,
+                    end: close + 1,
+                }
+            }
+            None => {
+                let end = line[pos..]
+                    .find(|c: char| c.is_whitespace() || matches!(c, '(' | '[' | '*'))
+                    .map_or(line.len(), |length| pos + length);
+                Token {
+                    kind: TokenKind::Text,
+                    text: &line[pos..end],
+                    start: pos,
+                    end,
+                }
+            }
+        };
+        pos = token.end;
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn is_set_code(text: &str) -> bool {
+    (1..=8).contains(&text.len()) && text.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// The text of a token without its delimiters, with its span.
+fn text_of<'a>(line: &str, token: &Token<'a>) -> Spanned<&'a str> {
+    let (start, end) = token.inner();
+    Spanned {
+        value: token.text,
+        span: Span::from_bytes(line, start, end),
+    }
+}
+
+/// Parses one trimmed, non-empty deck line. Errors are byte ranges of the line.
+pub(crate) fn parse_line(line_number: usize, line: &str) -> Result<DeckLine<'_>, LineError> {
+    let span = |start: usize, end: usize| Span::from_bytes(line, start, end);
+    let unexpected = |tokens: &[Token]| {
+        let (start, end) = (tokens[0].start, tokens[tokens.len() - 1].end);
+        (
+            start,
+            end,
+            ParseErrorKind::UnexpectedText {
+                text: line[start..end].to_string(),
+            },
+        )
+    };
+
+    let tokens = tokenize(line)?;
+    let Some((first, mut rest)) = tokens.split_first() else {
+        return Err((0, line.len(), ParseErrorKind::MissingQuantity));
+    };
+
+    // Quantity: digits with an optional x
+    let digits = first.text.strip_suffix(['x', 'X']).unwrap_or(first.text);
+    if first.kind != TokenKind::Text
+        || digits.is_empty()
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err((first.start, first.end, ParseErrorKind::MissingQuantity));
+    }
+    let quantity = match digits.parse::<u32>() {
+        Ok(quantity) if quantity > 0 => quantity,
+        _ => {
+            return Err((
+                first.start,
+                first.start + digits.len(),
+                ParseErrorKind::InvalidQuantity,
+            ));
+        }
+    };
+
+    // Categories: a bracketed, comma separated list at the very end
+    let mut categories = Vec::new();
+    if let Some((last, init)) = rest.split_last()
+        && last.kind == TokenKind::Brackets
+    {
+        let mut offset = last.start + 1;
+        for category in last.text.split(',') {
+            let trimmed = category.trim();
+            if !trimmed.is_empty() {
+                let start = offset + category.find(trimmed).unwrap_or(0);
+                categories.push(Spanned {
+                    value: trimmed,
+                    span: span(start, start + trimmed.len()),
+                });
+            }
+            offset += category.len() + 1;
+        }
+        rest = init;
+    }
+
+    // Finish markers, before the categories
+    let mut finish_tokens = Vec::new();
+    while let Some((last, init)) = rest.split_last()
+        && last.kind == TokenKind::Stars
+    {
+        finish_tokens.push(*last);
+        rest = init;
+    }
+    finish_tokens.reverse();
+    let mut finishes: Vec<Spanned<Finish>> = Vec::new();
+    for token in &finish_tokens {
+        let Some(finish) = Finish::from_marker(token.text) else {
+            return Err((
+                token.start,
+                token.end,
+                ParseErrorKind::UnknownFinish {
+                    marker: token.text.to_string(),
+                },
+            ));
+        };
+        if finishes.first().is_some_and(|first| first.value != finish) {
+            return Err((token.start, token.end, ParseErrorKind::ConflictingFinishes));
+        }
+        finishes.push(Spanned {
+            value: finish,
+            span: span(token.start, token.end),
+        });
+    }
+
+    // The printing: the last set code in parentheses, followed by at most a collector number
+    let mut set_code = None;
+    let mut collector_number = None;
+    if let Some(index) = rest
+        .iter()
+        .rposition(|token| token.kind == TokenKind::Parens && is_set_code(token.text))
+    {
+        match &rest[index + 1..] {
+            [] => {}
+            [number] if number.kind == TokenKind::Text => {
+                collector_number = Some(text_of(line, number));
+            }
+            [number, extra @ ..] if number.kind == TokenKind::Text => {
+                return Err(unexpected(extra));
+            }
+            extra => return Err(unexpected(extra)),
+        }
+        set_code = Some(text_of(line, &rest[index]));
+        rest = &rest[..index];
+    }
+
+    // The name is everything in between, which may contain parentheses but no other groups
+    if let Some(misplaced) = rest
+        .iter()
+        .position(|token| matches!(token.kind, TokenKind::Brackets | TokenKind::Stars))
+    {
+        return Err(unexpected(&rest[misplaced..misplaced + 1]));
+    }
+    let (Some(name_start), Some(name_end)) = (rest.first(), rest.last()) else {
+        let column = tokens.get(1).map_or(line.len(), |token| token.start);
+        return Err((column, column, ParseErrorKind::EmptyCardName));
+    };
+
+    Ok(DeckLine {
+        line_number,
+        line,
+        section: Section::Main,
+        quantity: Spanned {
+            value: quantity,
+            span: span(first.start, first.end),
+        },
+        name: Spanned {
+            value: Cow::Borrowed(&line[name_start.start..name_end.end]),
+            span: span(name_start.start, name_end.end),
+        },
+        reference: None,
+        set_code,
+        collector_number,
+        variant: None,
+        finishes,
+        categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> DeckLine<'_> {
+        parse_line(1, line).unwrap()
+    }
+
+    fn parse_error(line: &str) -> (ParseErrorKind, Span) {
+        let error = parse_deck_list(line).errors.remove(0);
+        (error.kind, error.span)
+    }
+
+    #[test]
+    fn test_parse_line_spans() {
+        let line = parse("2x Sol Ring (C21) 263 *F* *f* [Ramp, Artifact]");
+
+        assert_eq!(line.quantity.value, 2);
+        assert_eq!(line.quantity.span, Span { start: 0, end: 2 });
+        assert_eq!(line.name.value, "Sol Ring");
+        assert_eq!(line.name.span, Span { start: 3, end: 11 });
+        let set_code = line.set_code.unwrap();
+        assert_eq!(set_code.value, "C21");
+        assert_eq!(set_code.span, Span { start: 13, end: 16 });
+        assert_eq!(
+            line.collector_number.unwrap().span,
+            Span { start: 18, end: 21 }
+        );
+        assert_eq!(line.finishes.len(), 2);
+        assert_eq!(line.finishes[1].span, Span { start: 26, end: 29 });
+        assert_eq!(line.finish(), Some(Finish::Foil));
+        let categories: Vec<_> = line
+            .categories
+            .iter()
+            .map(|category| (category.value, category.span))
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                ("Ramp", Span { start: 31, end: 35 }),
+                ("Artifact", Span { start: 37, end: 45 })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_line_variants() {
+        let line = parse("1 B.F.M. (Big Furry Monster) (ugl) 28");
+        assert_eq!(line.name.value, "B.F.M. (Big Furry Monster)");
+        assert_eq!(line.set_code.unwrap().value, "ugl");
+
+        let line = parse("4 Forest (bfz)");
+        assert_eq!(line.name.value, "Forest");
+        assert_eq!(line.set_code.unwrap().value, "bfz");
+        assert_eq!(line.collector_number, None);
+
+        let line = parse("1X Fire // Ice [Removal]");
+        assert_eq!(line.name.value, "Fire // Ice");
+        assert_eq!(line.set_code, None);
+        assert_eq!(line.categories[0].value, "Removal");
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        fn assert_sections(input: &str, expected: &[(&str, Section)]) {
+            let parsed = parse_deck_list(input);
+            let sections: Vec<_> = parsed
+                .lines
+                .iter()
+                .map(|line| (&*line.name.value, line.section))
+                .collect();
+            assert_eq!(sections, expected);
+        }
+
+        let input = "Commander\n1 Atraxa\n\nDeck\n1 Sol Ring\n// Sideboard\n1 Negate\n\
+            Maybeboard (1):\n1 Opt\nCompanion\n1 Lurrus";
+        assert_sections(
+            input,
+            &[
+                ("Atraxa", Section::Commander),
+                ("Sol Ring", Section::Main),
+                ("Negate", Section::Sideboard),
+                ("Opt", Section::Maybeboard),
+                ("Lurrus", Section::Companion),
+            ],
+        );
+
+        // Blank lines alone never start a sideboard
+        assert_sections(
+            "4 Opt\n32 Lightning Bolt\n\n24 Mountain\n",
+            &[
+                ("Opt", Section::Main),
+                ("Lightning Bolt", Section::Main),
+                ("Mountain", Section::Main),
+            ],
+        );
+
+        assert_sections(
+            "1x Atraxa [Commander]\n1x Sol Ring [Ramp]",
+            &[("Atraxa", Section::Commander), ("Sol Ring", Section::Main)],
+        );
+    }
+
+    #[test]
+    fn test_parse_line_errors() {
+        assert_eq!(
+            parse_error("Forest"),
+            (ParseErrorKind::MissingQuantity, Span { start: 0, end: 6 })
+        );
+        assert_eq!(
+            parse_error("0x Forest"),
+            (ParseErrorKind::InvalidQuantity, Span { start: 0, end: 1 })
+        );
+        assert_eq!(
+            parse_error("1x [Land]"),
+            (ParseErrorKind::EmptyCardName, Span { start: 3, end: 3 })
+        );
+        assert_eq!(
+            parse_error("1x Forest (bfz 251"),
+            (
+                ParseErrorKind::Unterminated { expected: ')' },
+                Span { start: 10, end: 18 }
+            )
+        );
+        assert_eq!(
+            parse_error("1x Forest (bfz) 251 extra"),
+            (
+                ParseErrorKind::UnexpectedText {
+                    text: "extra".to_string()
+                },
+                Span { start: 20, end: 25 }
+            )
+        );
+        assert_eq!(
+            parse_error("1x Forest [Land] (bfz) 251"),
+            (
+                ParseErrorKind::UnexpectedText {
+                    text: "[Land]".to_string()
+                },
+                Span { start: 10, end: 16 }
+            )
+        );
+        assert_eq!(
+            parse_error("1x Forest (bfz) 251 *F* *E*"),
+            (
+                ParseErrorKind::ConflictingFinishes,
+                Span { start: 24, end: 27 }
+            )
+        );
+    }
+}
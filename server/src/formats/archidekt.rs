@@ -0,0 +1,119 @@
+//! Archidekt's text export, the native grammar with category modifiers and labels.
+//!
+//! ```text
+//! 1x Sol Ring (c21) 263 *F* [Ramp{top},Artifact] ^Have,#37d67a^
+//! ```
+//!
+//! Modifiers in braces after a category, such as `{top}` or `{noDeck}`, and the label between
+//! carets are dropped. Archidekt keeps the commander, sideboard and maybeboard as categories, so
+//! those categories put the card in that section, as does any category marked `{noDeck}`, which
+//! Archidekt leaves out of the deck.
+
+use super::category_section;
+use crate::deck_list::{
+    self, DeckLine, LineError, ParsedDeckList, Section, Span, parse_lines_with,
+};
+
+/// Whether a line ends with an Archidekt label.
+pub(super) fn has_label(line: &str) -> bool {
+    line.starts_with(|c: char| c.is_ascii_digit()) && line.ends_with('^')
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    let mut parsed = parse_lines_with(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line)),
+        parse_line,
+    );
+
+    for line in &mut parsed.lines {
+        let mut excluded = false;
+        for category in &mut line.categories {
+            if let Some((name, modifiers)) = category.value.split_once('{') {
+                excluded |= modifiers.contains("noDeck");
+                let name = name.trim_end();
+                category.value = name;
+                category.span = Span {
+                    start: category.span.start,
+                    end: category.span.start + name.chars().count(),
+                };
+            }
+        }
+        line.categories
+            .retain(|category| !category.value.is_empty());
+
+        if line.section == Section::Main {
+            line.section = match category_section(line) {
+                Some(section) => section,
+                None if excluded => Section::Maybeboard,
+                None => Section::Main,
+            };
+        }
+    }
+    parsed
+}
+
+fn parse_line(line_number: usize, line: &str) -> Result<DeckLine<'_>, LineError> {
+    let label_start = line
+        .strip_suffix('^')
+        .and_then(|rest| rest.rfind('^'))
+        .filter(|_| has_label(line));
+    let Some(label_start) = label_start else {
+        return deck_list::parse_line(line_number, line);
+    };
+    let mut parsed = deck_list::parse_line(line_number, line[..label_start].trim_end())?;
+    parsed.line = line;
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_archidekt_categories() {
+        let parsed = parse(
+            "1x Atraxa, Praetors' Voice (2x2) 190 [Commander{top}]\n\
+            1x Sol Ring (c21) 263 *F* [Ramp{top},Artifact] ^Have,#37d67a^\n\
+            1x Negate [Sideboard]\n\
+            1x Opt [Maybeboard{noDeck}{noPrice},Draw]\n\
+            1x Brainstorm [Cut{noDeck}]",
+        );
+        assert!(parsed.errors.is_empty());
+
+        let lines: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    &*line.name.value,
+                    line.section,
+                    line.categories
+                        .iter()
+                        .map(|category| category.value)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (
+                    "Atraxa, Praetors' Voice",
+                    Section::Commander,
+                    vec!["Commander"]
+                ),
+                ("Sol Ring", Section::Main, vec!["Ramp", "Artifact"]),
+                ("Negate", Section::Sideboard, vec!["Sideboard"]),
+                ("Opt", Section::Maybeboard, vec!["Maybeboard", "Draw"]),
+                ("Brainstorm", Section::Maybeboard, vec!["Cut"]),
+            ]
+        );
+        assert_eq!(
+            parsed.lines[1].categories[0].span,
+            Span { start: 27, end: 31 }
+        );
+    }
+}
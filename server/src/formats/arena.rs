@@ -0,0 +1,196 @@
+//! MTG Arena's deck export.
+//!
+//! ```text
+//! About
+//! Name Mono Red
+//!
+//! Deck
+//! 4 Lightning Bolt (M11) 149
+//!
+//! Sideboard
+//! 2 Abrade (DMU) 115
+//! ```
+//!
+//! The card lines are the native grammar without the `x`, and the section headers are the same,
+//! so only the `About` block and Arena's own set codes need handling here. Exports without
+//! headers put the sideboard after a blank line: when the cards form exactly two blocks and the
+//! second is smaller, the second block is the sideboard.
+
+use std::borrow::Cow;
+
+use crate::deck_list::{ParsedDeckList, Section, parse_lines};
+
+/// Arena set codes that differ from Scryfall's, with the Scryfall code. Only codes seen in Arena
+/// exports belong here; other sets use the same code in both.
+const SET_CODES: &[(&str, &str)] = &[
+    // Dominaria
+    ("dar", "dom"),
+];
+
+/// Whether a line is how Arena starts an export.
+pub(super) fn is_arena_start(line: &str) -> bool {
+    ["About", "Deck", "Commander", "Companion"].contains(&line)
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    let mut name = None;
+    let mut in_about = false;
+    let lines: Vec<_> = input
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let trimmed = line.trim();
+            if trimmed == "About" {
+                in_about = true;
+                return None;
+            }
+            if in_about {
+                if trimmed.is_empty() {
+                    in_about = false;
+                } else if let Some(deck_name) = trimmed.strip_prefix("Name ") {
+                    name = Some(Cow::Borrowed(deck_name.trim()));
+                }
+                return None;
+            }
+            Some((index + 1, line))
+        })
+        .collect();
+    let mut parsed = parse_lines(lines.iter().copied());
+    parsed.name = name;
+    split_sideboard(&lines, &mut parsed);
+
+    for line in &mut parsed.lines {
+        if let Some(set_code) = &mut line.set_code
+            && let Some(&(_, scryfall_code)) = SET_CODES
+                .iter()
+                .find(|(arena_code, _)| arena_code.eq_ignore_ascii_case(set_code.value))
+        {
+            set_code.value = scryfall_code;
+        }
+    }
+    parsed
+}
+
+/// Moves the second of exactly two blank-separated blocks of cards to the sideboard when it is
+/// the smaller one and the list has no headers.
+fn split_sideboard(lines: &[(usize, &str)], parsed: &mut ParsedDeckList) {
+    // The line number each block of lines starts on
+    let mut blocks = Vec::new();
+    let mut in_block = false;
+    for &(line_number, line) in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            in_block = false;
+        } else if Section::from_header(line).is_some() {
+            return;
+        } else if !line.starts_with('#') && !line.starts_with("//") && !in_block {
+            blocks.push(line_number);
+            in_block = true;
+        }
+    }
+
+    let [_, sideboard_start] = blocks[..] else {
+        return;
+    };
+    let (main_total, sideboard_total) =
+        parsed.lines.iter().fold((0, 0), |(main, sideboard), line| {
+            if line.line_number >= sideboard_start {
+                (main, sideboard + line.quantity.value)
+            } else {
+                (main + line.quantity.value, sideboard)
+            }
+        });
+    if sideboard_total < main_total {
+        for line in &mut parsed.lines {
+            if line.line_number >= sideboard_start {
+                line.section = Section::Sideboard;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck_list::Section;
+
+    #[test]
+    fn test_parse_arena_export() {
+        let input = "About\nName Mono Red Aggro\n\nCommander\n1 Krenko, Mob Boss (DDT) 52\n\n\
+            Companion\n1 Lurrus of the Dream-Den (IKO) 226\n\nDeck\n4 Lightning Bolt (M11) 149\n\
+            1 Llanowar Elves (DAR) 168\n20 Mountain\n\nSideboard\n2 Abrade (DMU) 115\n";
+        let parsed = parse(input);
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.name.as_deref(), Some("Mono Red Aggro"));
+        let lines: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.line_number,
+                    line.quantity.value,
+                    &*line.name.value,
+                    line.set_code.map(|set_code| set_code.value),
+                    line.section,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (5, 1, "Krenko, Mob Boss", Some("DDT"), Section::Commander),
+                (
+                    8,
+                    1,
+                    "Lurrus of the Dream-Den",
+                    Some("IKO"),
+                    Section::Companion
+                ),
+                (11, 4, "Lightning Bolt", Some("M11"), Section::Main),
+                (12, 1, "Llanowar Elves", Some("dom"), Section::Main),
+                (13, 20, "Mountain", None, Section::Main),
+                (16, 2, "Abrade", Some("DMU"), Section::Sideboard),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_headerless_arena_export() {
+        let sections = |input| {
+            parse(input)
+                .lines
+                .iter()
+                .map(|line| (line.name.value.to_string(), line.section))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sections("4 Opt\n20 Island\n\n2 Negate\n"),
+            vec![
+                ("Opt".to_string(), Section::Main),
+                ("Island".to_string(), Section::Main),
+                ("Negate".to_string(), Section::Sideboard),
+            ]
+        );
+        assert_eq!(
+            sections("2 Negate\n\n4 Opt\n\n20 Island"),
+            vec![
+                ("Negate".to_string(), Section::Main),
+                ("Opt".to_string(), Section::Main),
+                ("Island".to_string(), Section::Main),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_arena_set_codes() {
+        for &(arena_code, scryfall_code) in SET_CODES {
+            let input = format!("Deck\n1 Opt ({}) 1", arena_code.to_uppercase());
+            let parsed = parse(&input);
+            assert_eq!(
+                parsed.lines[0].set_code.map(|set_code| set_code.value),
+                Some(scryfall_code)
+            );
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Cockatrice's `.cod` deck files.
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <cockatrice_deck version="1">
+//!     <deckname>Mono Red</deckname>
+//!     <comments></comments>
+//!     <zone name="main">
+//!         <card number="4" name="Lightning Bolt" setShortName="M11" collectorNumber="149"/>
+//!     </zone>
+//!     <zone name="side">
+//!         <card number="2" name="Abrade"/>
+//!     </zone>
+//! </cockatrice_deck>
+//! ```
+//!
+//! Cockatrice only has a main deck and a sideboard, plus a `tokens` zone, which is skipped. The
+//! set and collector number are only written by recent versions.
+
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use super::xml::{Element, ElementError, read_events};
+use super::{add_copies, export_name};
+use crate::deck::DeckResolveResult;
+use crate::deck_list::{DeckLine, ParseErrorKind, ParsedDeckList, Section};
+
+/// Whether `input` is a `.cod` file rather than another XML format.
+pub(super) fn is_cod(input: &str) -> bool {
+    input.contains("<cockatrice_deck")
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    // The section of the zone being read, `None` outside zones and in skipped ones
+    let mut zone = None;
+    let mut name_start = None;
+    let mut name = None;
+
+    let mut parsed = read_events(input, |event, range| {
+        match event {
+            Event::Start(element) if element.name().as_ref() == "zone" => {
+                let element = Element::new(input, range, &element);
+                zone = match element.attribute("name") {
+                    Ok(Some(zone)) if zone == "main" => Some(Section::Main),
+                    Ok(Some(zone)) if zone == "side" => Some(Section::Sideboard),
+                    _ => None,
+                };
+            }
+            Event::End(element) if element.name().as_ref() == "zone" => zone = None,
+            Event::Start(element) if element.name().as_ref() == "deckname" => {
+                name_start = Some(range.end);
+            }
+            Event::End(element) if element.name().as_ref() == "deckname" => {
+                name = name_start
+                    .take()
+                    .and_then(|start| unescape(input[start..range.start].trim()).ok());
+            }
+            Event::Start(element) | Event::Empty(element) if element.name().as_ref() == "card" => {
+                let section = zone?;
+                return Some(read_card(&Element::new(input, range, &element), section));
+            }
+            _ => {}
+        }
+        None
+    });
+
+    parsed.name = name.filter(|name| !name.is_empty());
+    parsed
+}
+
+fn read_card<'a>(
+    element: &Element<'a, '_>,
+    section: Section,
+) -> Result<DeckLine<'a>, ElementError> {
+    let Some(name) = element.spanned("name")? else {
+        return Err(element.error("name", ParseErrorKind::EmptyCardName));
+    };
+
+    Ok(DeckLine {
+        line_number: element.line_number,
+        line: element.line,
+        section,
+        quantity: element.quantity("number")?,
+        name: name.map(Cow::Owned),
+        reference: None,
+        set_code: element.raw("setShortName"),
+        collector_number: element.raw("collectorNumber"),
+        variant: None,
+        finishes: Vec::new(),
+        categories: Vec::new(),
+    })
+}
+
+/// Writes a resolved deck as a `.cod` file. The commander stays in the main deck and the
+/// companion goes in the sideboard, as Cockatrice has no zones for them, and the maybeboard is
+/// left out.
+pub(super) fn export_cod(deck: &DeckResolveResult) -> String {
+    let mut main = Vec::new();
+    let mut side = Vec::new();
+    for entry in &deck.entries {
+        let zone = match entry.section {
+            Section::Commander | Section::Main => &mut main,
+            Section::Companion | Section::Sideboard => &mut side,
+            Section::Maybeboard => continue,
+        };
+        let printing = match entry.card {
+            Some(card) => (
+                export_name(card, " // "),
+                Some(card.set.to_uppercase()),
+                Some(card.collector_number.clone()),
+            ),
+            None => (
+                entry.name.clone(),
+                entry.set_code.as_deref().map(str::to_uppercase),
+                entry.collector_number.clone(),
+            ),
+        };
+        add_copies(zone, printing, entry.quantity);
+    }
+
+    let mut cod = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<cockatrice_deck version=\"1\">\n",
+    );
+    let _ = writeln!(
+        cod,
+        "    <deckname>{}</deckname>",
+        escape(deck.name.as_deref().unwrap_or_default())
+    );
+    cod.push_str("    <comments></comments>\n");
+    for (zone, cards) in [("main", main), ("side", side)] {
+        if cards.is_empty() {
+            continue;
+        }
+        let _ = writeln!(cod, "    <zone name=\"{zone}\">");
+        for ((name, set_code, collector_number), quantity) in cards {
+            let _ = write!(
+                cod,
+                "        <card number=\"{quantity}\" name=\"{}\"",
+                escape(name.as_str())
+            );
+            if let Some(set_code) = set_code {
+                let _ = write!(cod, " setShortName=\"{}\"", escape(set_code.as_str()));
+            }
+            if let Some(collector_number) = collector_number {
+                let _ = write!(
+                    cod,
+                    " collectorNumber=\"{}\"",
+                    escape(collector_number.as_str())
+                );
+            }
+            cod.push_str("/>\n");
+        }
+        cod.push_str("    </zone>\n");
+    }
+    cod.push_str("</cockatrice_deck>\n");
+    cod
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, CardDatabase};
+    use crate::deck::resolve_deck_list;
+    use crate::deck_list::Span;
+    use crate::formats::DeckFormat;
+    use crate::printings::PrintingPreference;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_cod() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<cockatrice_deck version="1">
+    <deckname>Tom &amp; Jerry</deckname>
+    <comments></comments>
+    <zone name="main">
+        <card number="4" name="Lightning Bolt" setShortName="M11" collectorNumber="149"/>
+        <card number="0" name="Opt"/>
+    </zone>
+    <zone name="side">
+        <card number="2" name="Abrade"/>
+    </zone>
+    <zone name="tokens">
+        <card number="1" name="Goblin"/>
+    </zone>
+</cockatrice_deck>
+"#;
+        let parsed = parse(input);
+
+        assert_eq!(parsed.name.as_deref(), Some("Tom & Jerry"));
+        let lines: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.line_number,
+                    line.quantity.value,
+                    &*line.name.value,
+                    line.set_code.map(|set_code| set_code.value),
+                    line.collector_number.map(|number| number.value),
+                    line.section,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (
+                    6,
+                    4,
+                    "Lightning Bolt",
+                    Some("M11"),
+                    Some("149"),
+                    Section::Main
+                ),
+                (10, 2, "Abrade", None, None, Section::Sideboard),
+            ]
+        );
+        assert_eq!(
+            parsed.lines[0].set_code.unwrap().span,
+            Span { start: 53, end: 56 }
+        );
+
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 7);
+        assert_eq!(parsed.errors[0].kind, ParseErrorKind::InvalidQuantity);
+    }
+
+    #[test]
+    fn test_cod_round_trip() {
+        let card = |name: &str, set: &str, collector_number: &str| Card {
+            id: format!("{set}-{collector_number}"),
+            name: name.into(),
+            set: set.into(),
+            collector_number: collector_number.to_string(),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            card("Lightning Bolt", "m11", "149"),
+            card("Lightning Bolt", "m10", "146"),
+            card("Abrade", "dmu", "115"),
+        ]));
+        let preference = PrintingPreference::default();
+        let deck = resolve_deck_list(
+            "Deck\n3x Lightning Bolt (m10) 146\n1x Lightning Bolt (m11) 149\n1x Black & White\n\n\
+                Sideboard\n2x Abrade (dmu) 115\nMaybeboard\n1x Abrade",
+            DeckFormat::Text,
+            &cards,
+            &preference,
+        );
+
+        let cod = export_cod(&deck);
+        assert!(cod.contains(
+            "        <card number=\"3\" name=\"Lightning Bolt\" setShortName=\"M10\" collectorNumber=\"146\"/>\n"
+        ));
+        assert!(cod.contains("        <card number=\"1\" name=\"Black &amp; White\"/>\n"));
+
+        let summary = |deck: &DeckResolveResult| {
+            deck.entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.card_name().to_string(),
+                        entry.quantity,
+                        entry.section,
+                        entry.card.map(|card| card.id.clone()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let imported = resolve_deck_list(&cod, DeckFormat::Auto, &cards, &preference);
+        let mut expected = summary(&deck);
+        expected.pop();
+        assert_eq!(summary(&imported), expected);
+    }
+}
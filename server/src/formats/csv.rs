@@ -0,0 +1,334 @@
+//! CSV exports, such as Moxfield's and Archidekt's.
+//!
+//! ```text
+//! "Count","Tradelist Count","Name","Edition","Condition","Language","Foil","Tags",...
+//! "1","0","Sol Ring","c21","Near Mint","English","foil","Ramp,Artifact",...
+//! ```
+//!
+//! Columns are found by their header, so exports with extra or reordered columns work, and
+//! unknown columns are ignored. Only the name is required; without a quantity column every row
+//! is one copy. Quoted fields may not span lines.
+
+use std::borrow::Cow;
+
+use super::category_section;
+use crate::deck_list::{
+    CardReference, DeckLine, Finish, LineError, ParseError, ParseErrorKind, ParsedDeckList,
+    Section, Span, Spanned,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Quantity,
+    Name,
+    SetCode,
+    CollectorNumber,
+    Finish,
+    Tags,
+    Section,
+    ScryfallId,
+    MtgoId,
+}
+
+impl Column {
+    fn from_header(header: &str) -> Option<Self> {
+        match header.trim().to_lowercase().as_str() {
+            "count" | "quantity" | "qty" => Some(Column::Quantity),
+            "name" | "card name" => Some(Column::Name),
+            "edition" | "edition code" | "set" | "set code" => Some(Column::SetCode),
+            "collector number" | "card number" => Some(Column::CollectorNumber),
+            "foil" | "finish" => Some(Column::Finish),
+            "tags" | "category" | "categories" => Some(Column::Tags),
+            "board" | "section" => Some(Column::Section),
+            "scryfall id" => Some(Column::ScryfallId),
+            "mtgo id" => Some(Column::MtgoId),
+            _ => None,
+        }
+    }
+}
+
+/// The columns a header line names, or `None` if it is not a CSV header with a name.
+fn read_header(line: &str) -> Option<Vec<Option<Column>>> {
+    let fields = split_fields(line).ok()?;
+    let columns: Vec<_> = fields
+        .iter()
+        .map(|&(start, end)| Column::from_header(&line[start..end]))
+        .collect();
+    columns.contains(&Some(Column::Name)).then_some(columns)
+}
+
+/// Whether a line is a CSV header naming both the card and the quantity.
+pub(super) fn is_csv_header(line: &str) -> bool {
+    read_header(line).is_some_and(|columns| columns.contains(&Some(Column::Quantity)))
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    let mut parsed = ParsedDeckList::default();
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let Some((header_number, header)) = lines.next() else {
+        return parsed;
+    };
+    let Some(columns) = read_header(header) else {
+        parsed.errors.push(ParseError::new(
+            header_number,
+            header,
+            Span::from_bytes(header, 0, header.len()),
+            ParseErrorKind::InvalidFile {
+                message: "The header has no Name column".to_string(),
+            },
+        ));
+        return parsed;
+    };
+
+    for (line_number, line) in lines {
+        match parse_row(line_number, line, &columns) {
+            Ok(mut line) => {
+                if line.section == Section::Main
+                    && let Some(section) = category_section(&line)
+                {
+                    line.section = section;
+                }
+                parsed.lines.push(line)
+            }
+            Err((start, end, kind)) => parsed.errors.push(ParseError::new(
+                line_number,
+                line,
+                Span::from_bytes(line, start, end),
+                kind,
+            )),
+        }
+    }
+    parsed
+}
+
+/// Byte ranges of the fields of a line, without their quotes.
+fn split_fields(line: &str) -> Result<Vec<(usize, usize)>, LineError> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    loop {
+        let end = if line[pos..].starts_with('"') {
+            let start = pos + 1;
+            // A quote inside a quoted field is written twice
+            let mut end = start;
+            loop {
+                match line[end..].find('"') {
+                    Some(length) if line[end + length + 1..].starts_with('"') => {
+                        end += length + 2;
+                    }
+                    Some(length) => {
+                        end += length;
+                        break;
+                    }
+                    None => {
+                        return Err((
+                            pos,
+                            line.len(),
+                            ParseErrorKind::Unterminated { expected: '"' },
+                        ));
+                    }
+                }
+            }
+            fields.push((start, end));
+            end + 1
+        } else {
+            let end = line[pos..]
+                .find(',')
+                .map_or(line.len(), |length| pos + length);
+            fields.push((pos, end));
+            end
+        };
+        match line[end..].find(',') {
+            Some(length) => pos = end + length + 1,
+            None => return Ok(fields),
+        }
+    }
+}
+
+fn parse_row<'a>(
+    line_number: usize,
+    line: &'a str,
+    columns: &[Option<Column>],
+) -> Result<DeckLine<'a>, LineError> {
+    let fields = split_fields(line)?;
+    // The trimmed text of a column's field and its byte range, if the row has the column
+    let field = |column: Column| {
+        let index = columns.iter().position(|&c| c == Some(column))?;
+        let &(start, end) = fields.get(index)?;
+        let text = &line[start..end];
+        let start = start + (text.len() - text.trim_start().len());
+        Some((text.trim(), start, start + text.trim().len()))
+    };
+    let spanned = |(text, start, end): (&'a str, usize, usize)| Spanned {
+        value: text,
+        span: Span::from_bytes(line, start, end),
+    };
+    let non_empty = |column| field(column).filter(|(text, _, _)| !text.is_empty());
+
+    let quantity = match field(Column::Quantity) {
+        None if !columns.contains(&Some(Column::Quantity)) => Spanned {
+            value: 1,
+            span: Span { start: 0, end: 0 },
+        },
+        Some((text, start, end)) if !text.is_empty() => match text.parse() {
+            Ok(quantity) if quantity > 0 => spanned((text, start, end)).map(|_| quantity),
+            _ => return Err((start, end, ParseErrorKind::InvalidQuantity)),
+        },
+        _ => return Err((0, line.len(), ParseErrorKind::MissingQuantity)),
+    };
+
+    let Some(name) = non_empty(Column::Name) else {
+        let column = field(Column::Name).map_or(line.len(), |(_, start, _)| start);
+        return Err((column, column, ParseErrorKind::EmptyCardName));
+    };
+
+    let mut finishes = Vec::new();
+    if let Some((text, start, end)) = non_empty(Column::Finish) {
+        let finish = match text.to_lowercase().as_str() {
+            "normal" | "nonfoil" | "false" | "no" => None,
+            "foil" | "true" | "yes" => Some(Finish::Foil),
+            "etched" => Some(Finish::Etched),
+            _ => {
+                return Err((
+                    start,
+                    end,
+                    ParseErrorKind::UnknownFinish {
+                        marker: text.to_string(),
+                    },
+                ));
+            }
+        };
+        finishes.extend(finish.map(|finish| spanned((text, start, end)).map(|_| finish)));
+    }
+
+    let mut categories = Vec::new();
+    if let Some((text, start, _)) = non_empty(Column::Tags) {
+        let mut offset = start;
+        for tag in text.split(',') {
+            let trimmed = tag.trim();
+            if !trimmed.is_empty() {
+                let start = offset + (tag.len() - tag.trim_start().len());
+                categories.push(spanned((trimmed, start, start + trimmed.len())));
+            }
+            offset += tag.len() + 1;
+        }
+    }
+
+    let reference = match (non_empty(Column::ScryfallId), non_empty(Column::MtgoId)) {
+        (Some((id, _, _)), _) => Some(CardReference::Scryfall(id)),
+        (None, Some((id, _, _))) => id.parse().ok().map(CardReference::Mtgo),
+        (None, None) => None,
+    };
+
+    Ok(DeckLine {
+        line_number,
+        line,
+        section: non_empty(Column::Section)
+            .and_then(|(text, _, _)| Section::from_header(text))
+            .unwrap_or_default(),
+        quantity,
+        name: spanned(name).map(unescape),
+        reference,
+        set_code: non_empty(Column::SetCode).map(spanned),
+        collector_number: non_empty(Column::CollectorNumber).map(spanned),
+        variant: None,
+        finishes,
+        categories,
+    })
+}
+
+/// A quoted field's text with doubled quotes written once.
+fn unescape(text: &str) -> Cow<'_, str> {
+    if text.contains("\"\"") {
+        Cow::Owned(text.replace("\"\"", "\""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fields() {
+        let fields = |line| {
+            split_fields(line)
+                .unwrap()
+                .into_iter()
+                .map(|(start, end)| &line[start..end])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields("1,Sol Ring,,c21"), vec!["1", "Sol Ring", "", "c21"]);
+        assert_eq!(
+            fields(r#""1","Kongming, ""Sleeping Dragon""",x"#),
+            vec!["1", r#"Kongming, ""Sleeping Dragon"""#, "x"]
+        );
+        assert_eq!(fields("1,"), vec!["1", ""]);
+        assert_eq!(
+            split_fields(r#"1,"Sol Ring"#).unwrap_err(),
+            (2, 11, ParseErrorKind::Unterminated { expected: '"' })
+        );
+    }
+
+    #[test]
+    fn test_parse_moxfield_csv() {
+        let input = "\"Count\",\"Tradelist Count\",\"Name\",\"Edition\",\"Condition\",\"Language\",\"Foil\",\"Tags\",\"Last Modified\",\"Collector Number\"\n\
+            \"1\",\"0\",\"Sol Ring\",\"c21\",\"Near Mint\",\"English\",\"foil\",\"Ramp, Artifact\",\"2024-01-01\",\"263\"\n\
+            \"2\",\"0\",\"Kongming, \"\"Sleeping Dragon\"\"\",\"pca\",\"Near Mint\",\"English\",\"\",\"\",\"2024-01-01\",\"1\"\n\
+            \"x\",\"0\",\"Opt\",\"xln\",\"Near Mint\",\"English\",\"\",\"\",\"2024-01-01\",\"65\"\n";
+        assert!(is_csv_header(input.lines().next().unwrap()));
+        let parsed = parse(input);
+
+        let sol_ring = &parsed.lines[0];
+        assert_eq!(sol_ring.quantity.value, 1);
+        assert_eq!(sol_ring.name.value, "Sol Ring");
+        assert_eq!(sol_ring.name.span, Span { start: 9, end: 17 });
+        assert_eq!(
+            sol_ring.set_code.map(|set_code| set_code.value),
+            Some("c21")
+        );
+        assert_eq!(
+            sol_ring.collector_number.map(|number| number.value),
+            Some("263")
+        );
+        assert_eq!(sol_ring.finish(), Some(Finish::Foil));
+        let tags: Vec<_> = sol_ring.categories.iter().map(|tag| tag.value).collect();
+        assert_eq!(tags, vec!["Ramp", "Artifact"]);
+
+        assert_eq!(parsed.lines[1].name.value, "Kongming, \"Sleeping Dragon\"");
+        assert_eq!(parsed.lines[1].finish(), None);
+
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 4);
+        assert_eq!(parsed.errors[0].kind, ParseErrorKind::InvalidQuantity);
+        assert_eq!(parsed.errors[0].span, Span { start: 1, end: 2 });
+    }
+
+    #[test]
+    fn test_parse_archidekt_csv() {
+        let input = "Quantity,Name,Finish,Edition Name,Edition Code,Scryfall ID,Collector Number,Categories\n\
+            1,Negate,Etched,Magic 2014,m14,,,Sideboard\n\
+            1,Opt,Normal,Ixalan,xln,0b9f3cbd-0000-0000-0000-000000000000,65,\"Draw,Maybeboard\"\n";
+        let parsed = parse(input);
+        assert!(parsed.errors.is_empty());
+
+        assert_eq!(parsed.lines[0].section, Section::Sideboard);
+        assert_eq!(parsed.lines[0].finish(), Some(Finish::Etched));
+        assert_eq!(parsed.lines[0].reference, None);
+        assert_eq!(parsed.lines[1].section, Section::Maybeboard);
+        assert_eq!(
+            parsed.lines[1].reference,
+            Some(CardReference::Scryfall(
+                "0b9f3cbd-0000-0000-0000-000000000000"
+            ))
+        );
+
+        let errors = parse("Count,Card\n1,Sol Ring").errors;
+        assert!(matches!(errors[0].kind, ParseErrorKind::InvalidFile { .. }));
+    }
+}
@@ -0,0 +1,360 @@
+//! Forge's `.dck` deck files, INI-like sections of `{quantity} {name}|{set}|{art}` lines.
+//!
+//! ```text
+//! [metadata]
+//! Name=Mono Red
+//! [Commander]
+//! 1 Krenko, Mob Boss|DDT|1
+//! [Main]
+//! 4 Lightning Bolt|M11|1
+//! 20 Mountain
+//! [Sideboard]
+//! 2 Abrade|DMU
+//! ```
+//!
+//! The set and art index are optional. The art index numbers the card's printings in the set by
+//! collector number, starting from 1, and any further fields are ignored. Sections Forge uses for
+//! other game types, such as `[Planes]`, are skipped.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use super::{add_copies, export_name};
+use crate::cards::CardMap;
+use crate::deck::DeckResolveResult;
+use crate::deck_list::{
+    DeckLine, LineError, ParseError, ParseErrorKind, ParsedDeckList, Section, Span, Spanned,
+};
+
+/// The sections Forge writes for a constructed deck, in order.
+const SECTIONS: &[(&str, Section)] = &[
+    ("Commander", Section::Commander),
+    ("Main", Section::Main),
+    ("Sideboard", Section::Sideboard),
+];
+
+/// Whether a line is how Forge starts a deck file.
+pub(super) fn is_dck_start(line: &str) -> bool {
+    line.eq_ignore_ascii_case("[metadata]")
+        || SECTIONS
+            .iter()
+            .any(|(name, _)| line.eq_ignore_ascii_case(&format!("[{name}]")))
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    let mut parsed = ParsedDeckList::default();
+    // `None` in sections that are skipped
+    let mut section = Some(Section::Main);
+    let mut in_metadata = false;
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            in_metadata = header.eq_ignore_ascii_case("metadata");
+            section = SECTIONS
+                .iter()
+                .find(|(name, _)| header.eq_ignore_ascii_case(name))
+                .map(|&(_, section)| section);
+            continue;
+        }
+        if in_metadata {
+            if let Some(name) = line.strip_prefix("Name=") {
+                parsed.name = Some(Cow::Borrowed(name.trim()));
+            }
+            continue;
+        }
+        let Some(section) = section else {
+            continue;
+        };
+
+        match parse_line(line_number, line) {
+            Ok(mut card) => {
+                card.section = section;
+                parsed.lines.push(card);
+            }
+            Err((start, end, kind)) => parsed.errors.push(ParseError::new(
+                line_number,
+                line,
+                Span::from_bytes(line, start, end),
+                kind,
+            )),
+        }
+    }
+    parsed
+}
+
+/// Parses one trimmed card line. Errors are byte ranges of the line.
+fn parse_line<'a>(line_number: usize, line: &'a str) -> Result<DeckLine<'a>, LineError> {
+    let quantity_end = line.find(char::is_whitespace).unwrap_or(line.len());
+    let digits = line[..quantity_end]
+        .strip_suffix(['x', 'X'])
+        .unwrap_or(&line[..quantity_end]);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err((0, quantity_end, ParseErrorKind::MissingQuantity));
+    }
+    let quantity = match digits.parse::<u32>() {
+        Ok(quantity) if quantity > 0 => quantity,
+        _ => return Err((0, digits.len(), ParseErrorKind::InvalidQuantity)),
+    };
+
+    // The trimmed fields after the quantity, separated by `|`, with their byte ranges
+    let mut fields = Vec::new();
+    let mut offset = quantity_end;
+    for field in line[quantity_end..].split('|') {
+        let start = offset + (field.len() - field.trim_start().len());
+        fields.push((field.trim(), start, start + field.trim().len()));
+        offset += field.len() + 1;
+    }
+    let spanned = |(text, start, end): (&'a str, usize, usize)| Spanned {
+        value: text,
+        span: Span::from_bytes(line, start, end),
+    };
+
+    let name = fields[0];
+    if name.0.is_empty() {
+        return Err((name.1, name.1, ParseErrorKind::EmptyCardName));
+    }
+    let set_code = fields.get(1).filter(|set| !set.0.is_empty());
+    let variant = match fields.get(2).filter(|art| !art.0.is_empty()) {
+        Some(&(text, start, end)) => match text.parse::<u32>() {
+            Ok(variant) if variant > 0 => Some(spanned((text, start, end)).map(|_| variant)),
+            _ => {
+                return Err((
+                    start,
+                    end,
+                    ParseErrorKind::UnexpectedText {
+                        text: text.to_string(),
+                    },
+                ));
+            }
+        },
+        None => None,
+    };
+
+    Ok(DeckLine {
+        line_number,
+        line,
+        section: Section::Main,
+        quantity: Spanned {
+            value: quantity,
+            span: Span::from_bytes(line, 0, quantity_end),
+        },
+        name: spanned(name).map(Cow::Borrowed),
+        reference: None,
+        set_code: set_code.copied().map(spanned),
+        collector_number: None,
+        variant,
+        finishes: Vec::new(),
+        categories: Vec::new(),
+    })
+}
+
+/// Writes a resolved deck as a `.dck` file, with the art index of each printing. The companion
+/// goes in the sideboard, as Forge has no section for it, and the maybeboard is left out.
+pub(super) fn export_dck(deck: &DeckResolveResult, cards: &CardMap) -> String {
+    let mut sections: Vec<(Section, Vec<_>)> = SECTIONS
+        .iter()
+        .map(|&(_, section)| (section, Vec::new()))
+        .collect();
+    for entry in &deck.entries {
+        let section = match entry.section {
+            Section::Companion => Section::Sideboard,
+            Section::Maybeboard => continue,
+            section => section,
+        };
+        let printing = match entry.card {
+            Some(card) => {
+                let variant = cards
+                    .printings_in_set(&card.name, &card.set)
+                    .iter()
+                    .position(|printing| printing.collector_number == card.collector_number)
+                    .map(|index| index + 1);
+                (
+                    export_name(card, " // "),
+                    Some(card.set.to_uppercase()),
+                    variant,
+                )
+            }
+            None => (
+                entry.name.clone(),
+                entry.set_code.as_deref().map(str::to_uppercase),
+                None,
+            ),
+        };
+        if let Some((_, lines)) = sections.iter_mut().find(|(s, _)| *s == section) {
+            add_copies(lines, printing, entry.quantity);
+        }
+    }
+
+    // A line break in the name would start a new line, or even a section
+    let name = deck
+        .name
+        .as_deref()
+        .unwrap_or("Deck")
+        .replace(['\r', '\n'], " ");
+    let mut dck = String::from("[metadata]\n");
+    let _ = writeln!(dck, "Name={}", name.trim());
+    for ((name, _), (_, lines)) in SECTIONS.iter().zip(sections) {
+        if lines.is_empty() {
+            continue;
+        }
+        let _ = writeln!(dck, "[{name}]");
+        for ((name, set_code, variant), quantity) in lines {
+            let _ = write!(dck, "{quantity} {name}");
+            if let Some(set_code) = set_code {
+                let _ = write!(dck, "|{set_code}");
+                if let Some(variant) = variant {
+                    let _ = write!(dck, "|{variant}");
+                }
+            }
+            dck.push('\n');
+        }
+    }
+    dck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, CardDatabase};
+    use crate::deck::resolve_deck_list;
+    use crate::formats::DeckFormat;
+    use crate::printings::PrintingPreference;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_dck() {
+        let parsed = parse(
+            "[metadata]\nName=Mono Red\n[Commander]\n1 Krenko, Mob Boss|DDT|1\n[Main]\n\
+                4 Lightning Bolt|M11|2\n20 Mountain\n[Sideboard]\n2 Abrade|DMU\n1 Opt|XLN|x\n\
+                [Planes]\n1 Tazeem",
+        );
+
+        assert_eq!(parsed.name.as_deref(), Some("Mono Red"));
+        let lines: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.quantity.value,
+                    &*line.name.value,
+                    line.set_code.map(|set_code| set_code.value),
+                    line.variant.map(|variant| variant.value),
+                    line.section,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (
+                    1,
+                    "Krenko, Mob Boss",
+                    Some("DDT"),
+                    Some(1),
+                    Section::Commander
+                ),
+                (4, "Lightning Bolt", Some("M11"), Some(2), Section::Main),
+                (20, "Mountain", None, None, Section::Main),
+                (2, "Abrade", Some("DMU"), None, Section::Sideboard),
+            ]
+        );
+        assert_eq!(parsed.lines[1].name.span, Span { start: 2, end: 16 });
+        assert_eq!(
+            parsed.lines[1].variant.unwrap().span,
+            Span { start: 21, end: 22 }
+        );
+
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 10);
+        assert_eq!(parsed.errors[0].span, Span { start: 10, end: 11 });
+    }
+
+    #[test]
+    fn test_dck_round_trip() {
+        let card = |name: &str, set: &str, collector_number: &str| Card {
+            id: format!("{set}-{collector_number}"),
+            name: name.into(),
+            set: set.into(),
+            collector_number: collector_number.to_string(),
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            card("Swamp", "bfz", "260"),
+            card("Swamp", "bfz", "258a"),
+            card("Swamp", "bfz", "258"),
+            card("Krenko, Mob Boss", "ddt", "52"),
+            card("Lurrus of the Dream-Den", "iko", "226"),
+        ]));
+        let preference = PrintingPreference::default();
+        let deck = resolve_deck_list(
+            "Commander\n1x Krenko, Mob Boss\nDeck\n2x Swamp (bfz) 258a\n3x Swamp (bfz) 260\n\
+                1x Black & White\nSideboard\n1x Abrade (dmu)",
+            DeckFormat::Text,
+            &cards,
+            &preference,
+        );
+
+        let dck = export_dck(&deck, &cards);
+        assert_eq!(
+            dck,
+            "[metadata]\nName=Deck\n[Commander]\n1 Krenko, Mob Boss|DDT|1\n[Main]\n\
+                2 Swamp|BFZ|2\n3 Swamp|BFZ|3\n1 Black & White\n[Sideboard]\n1 Abrade|DMU\n"
+        );
+
+        let summary = |deck: &DeckResolveResult| {
+            deck.entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.card_name().to_string(),
+                        entry.quantity,
+                        entry.section,
+                        entry.card.map(|card| card.id.clone()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let imported = resolve_deck_list(&dck, DeckFormat::Auto, &cards, &preference);
+        assert_eq!(summary(&imported), summary(&deck));
+    }
+
+    #[test]
+    fn test_dck_name_with_line_breaks() {
+        let cards = Arc::new(CardDatabase::new(vec![Card {
+            id: "m11-149".to_string(),
+            name: "Lightning Bolt".into(),
+            set: "m11".into(),
+            collector_number: "149".to_string(),
+            ..Default::default()
+        }]));
+        let preference = PrintingPreference::default();
+        let cod = "<cockatrice_deck version=\"1\">\n<deckname>Mono Red\r\n[Sideboard]\n4 Lightning Bolt\
+            </deckname>\n<zone name=\"main\">\n<card number=\"1\" name=\"Lightning Bolt\"/>\n</zone>\n\
+            </cockatrice_deck>";
+        let deck = resolve_deck_list(cod, DeckFormat::CockatriceCod, &cards, &preference);
+
+        let dck = export_dck(&deck, &cards);
+        assert!(
+            dck.starts_with("[metadata]\nName=Mono Red  [Sideboard] 4 Lightning Bolt\n[Main]\n")
+        );
+        let imported = resolve_deck_list(&dck, DeckFormat::ForgeDck, &cards, &preference);
+        assert_eq!(
+            imported.name.as_deref(),
+            Some("Mono Red  [Sideboard] 4 Lightning Bolt")
+        );
+        let sections: Vec<_> = imported
+            .entries
+            .iter()
+            .map(|entry| (entry.quantity, entry.section))
+            .collect();
+        assert_eq!(sections, vec![(1, Section::Main)]);
+    }
+}
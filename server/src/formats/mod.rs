@@ -0,0 +1,231 @@
+//! Deck list formats exported by other tools.
+//!
+//! Every format is read into the same [`ParsedDeckList`] as the native text format, so the
+//! resolution and diffing code does not need to know where a list came from.
+
+mod archidekt;
+mod arena;
+mod cockatrice;
+mod csv;
+mod forge;
+mod moxfield;
+mod mtgo;
+mod xml;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::{Card, CardMap};
+use crate::deck::DeckResolveResult;
+use crate::deck_list::{DeckLine, ParsedDeckList, Section, parse_deck_list};
+
+/// The format of a deck list, chosen with the `format` query parameter. Lists are read as text
+/// unless another format or `auto` is asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckFormat {
+    /// Guess the format from the content
+    Auto,
+    /// This tool's own format, `1x Sol Ring (c21) 263 *F* [Ramp]`
+    #[default]
+    Text,
+    /// MTG Arena's export, `4 Lightning Bolt (M11) 149` with `Deck` and `Sideboard` headers
+    Arena,
+    /// Magic Online's `.dek` XML file
+    MtgoDek,
+    /// Magic Online's `.txt` export, `4 Lightning Bolt` with the sideboard after a blank line
+    MtgoText,
+    /// Moxfield's text export, the text format with `#tags` after the card
+    Moxfield,
+    /// Archidekt's text export, the text format with `{modifiers}` on categories and a `^label^`
+    Archidekt,
+    /// CSV with a header row, as exported by Moxfield and Archidekt
+    Csv,
+    /// Cockatrice's `.cod` XML file
+    CockatriceCod,
+    /// Forge's `.dck` file, with `[Main]` and `[Sideboard]` sections of `4 Lightning Bolt|M11|1`
+    ForgeDck,
+}
+
+impl DeckFormat {
+    /// The format of `input`, recognized by what the other tools write first, or otherwise by
+    /// card lines only Moxfield or Archidekt write, which the text format would reject.
+    pub fn detect(input: &str) -> Self {
+        let first_line = input
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        if first_line.starts_with('<') && cockatrice::is_cod(input) {
+            DeckFormat::CockatriceCod
+        } else if mtgo::is_dek_start(first_line) {
+            DeckFormat::MtgoDek
+        } else if arena::is_arena_start(first_line) {
+            DeckFormat::Arena
+        } else if forge::is_dck_start(first_line) {
+            DeckFormat::ForgeDck
+        } else if csv::is_csv_header(first_line) {
+            DeckFormat::Csv
+        } else if input.lines().any(|line| moxfield::has_tags(line.trim())) {
+            DeckFormat::Moxfield
+        } else if input.lines().any(|line| archidekt::has_label(line.trim())) {
+            DeckFormat::Archidekt
+        } else {
+            DeckFormat::Text
+        }
+    }
+
+    pub fn parse(self, input: &str) -> ParsedDeckList<'_> {
+        match self {
+            DeckFormat::Auto => DeckFormat::detect(input).parse(input),
+            DeckFormat::Text => parse_deck_list(input),
+            DeckFormat::Arena => arena::parse(input),
+            DeckFormat::MtgoDek => mtgo::parse_dek(input),
+            DeckFormat::MtgoText => mtgo::parse_text(input),
+            DeckFormat::Moxfield => moxfield::parse(input),
+            DeckFormat::Archidekt => archidekt::parse(input),
+            DeckFormat::Csv => csv::parse(input),
+            DeckFormat::CockatriceCod => cockatrice::parse(input),
+            DeckFormat::ForgeDck => forge::parse(input),
+        }
+    }
+}
+
+/// The section named by one of a line's categories, for tools that keep sections as categories.
+fn category_section(line: &DeckLine) -> Option<Section> {
+    line.categories
+        .iter()
+        .filter_map(|category| Section::from_header(category.value))
+        .find(|&section| section != Section::Main)
+}
+
+/// The `format` query parameter of the deck endpoints.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct FormatQuery {
+    #[serde(default)]
+    pub format: DeckFormat,
+}
+
+/// A file format resolved decks can be written in, chosen with the `to` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    MtgoDek,
+    CockatriceCod,
+    ForgeDck,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::MtgoDek | ExportFormat::CockatriceCod => "application/xml",
+            ExportFormat::ForgeDck => "text/plain; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::MtgoDek => "dek",
+            ExportFormat::CockatriceCod => "cod",
+            ExportFormat::ForgeDck => "dck",
+        }
+    }
+
+    pub fn export(self, deck: &DeckResolveResult, cards: &CardMap) -> String {
+        match self {
+            ExportFormat::MtgoDek => mtgo::export_dek(deck),
+            ExportFormat::CockatriceCod => cockatrice::export_cod(deck),
+            ExportFormat::ForgeDck => forge::export_dck(deck, cards),
+        }
+    }
+}
+
+/// The name other tools know a card by: split cards by their halves joined with `separator`,
+/// and other multi-faced cards by their front face.
+fn export_name(card: &Card, separator: &str) -> String {
+    match card.layout.as_str() {
+        "split" => card.face_names().collect::<Vec<_>>().join(separator),
+        _ => card.face_names().next().unwrap_or(&card.name).to_string(),
+    }
+}
+
+/// Adds copies to the group with the same key, or starts a group, keeping the order groups
+/// were first seen in.
+fn add_copies<K: PartialEq>(groups: &mut Vec<(K, u32)>, key: K, quantity: u32) {
+    match groups.iter_mut().find(|(group, _)| *group == key) {
+        Some((_, total)) => *total += quantity,
+        None => groups.push((key, quantity)),
+    }
+}
+
+/// The query parameters of the export endpoint.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ExportQuery {
+    pub to: ExportFormat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            DeckFormat::detect("\nAbout\nName Mono Red"),
+            DeckFormat::Arena
+        );
+        assert_eq!(DeckFormat::detect("Deck\n4 Opt"), DeckFormat::Arena);
+        assert_eq!(
+            DeckFormat::detect("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Deck>"),
+            DeckFormat::MtgoDek
+        );
+        assert_eq!(
+            DeckFormat::detect("\"Count\",\"Name\",\"Edition\"\n\"1\",\"Opt\",\"xln\""),
+            DeckFormat::Csv
+        );
+        assert_eq!(
+            DeckFormat::detect("1 Opt\n1 Sol Ring (C21) 263 #Ramp"),
+            DeckFormat::Moxfield
+        );
+        assert_eq!(
+            DeckFormat::detect("1x Opt\n1x Sol Ring [Ramp] ^Have,#37d67a^"),
+            DeckFormat::Archidekt
+        );
+        assert_eq!(
+            DeckFormat::detect("<?xml version=\"1.0\"?>\n<cockatrice_deck version=\"1\">"),
+            DeckFormat::CockatriceCod
+        );
+        assert_eq!(
+            DeckFormat::detect("[metadata]\nName=Mono Red\n[Main]\n4 Opt|XLN"),
+            DeckFormat::ForgeDck
+        );
+        assert_eq!(DeckFormat::detect("1x Sol Ring"), DeckFormat::Text);
+        assert_eq!(DeckFormat::detect(""), DeckFormat::Text);
+    }
+
+    #[test]
+    fn test_text_is_default() {
+        assert_eq!(DeckFormat::default(), DeckFormat::Text);
+        let input = "1 Fire // Ice #sideboard-note";
+        assert_eq!(DeckFormat::detect(input), DeckFormat::Moxfield);
+        assert_eq!(
+            DeckFormat::default().parse(input).lines[0].name.value,
+            "Fire // Ice #sideboard-note"
+        );
+    }
+
+    #[test]
+    fn test_text_without_headers_is_main_deck() {
+        let input = "4 Opt\n32 Lightning Bolt\n\n24 Mountain\n";
+        let parsed = DeckFormat::Text.parse(input);
+        assert_eq!(parsed.lines.len(), 3);
+        assert!(
+            parsed
+                .lines
+                .iter()
+                .all(|line| line.section == Section::Main)
+        );
+
+        let parsed = DeckFormat::Arena.parse(input);
+        assert_eq!(parsed.lines[2].section, Section::Sideboard);
+    }
+}
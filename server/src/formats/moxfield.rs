@@ -0,0 +1,91 @@
+//! Moxfield's text export, the native grammar with tags after the card.
+//!
+//! ```text
+//! 1 Sol Ring (C21) 263 *F* #Ramp #!Mana Rock
+//! ```
+//!
+//! Tags start with `#`, or `#!` for tags shared between decks, and may contain spaces. Card names
+//! never contain `#`, so the first ` #` on a line starts the tags.
+
+use crate::deck_list::{
+    self, DeckLine, LineError, ParsedDeckList, Span, Spanned, parse_lines_with,
+};
+
+/// Whether a line has Moxfield tags.
+pub(super) fn has_tags(line: &str) -> bool {
+    line.starts_with(|c: char| c.is_ascii_digit()) && line.contains(" #")
+}
+
+pub(super) fn parse(input: &str) -> ParsedDeckList<'_> {
+    parse_lines_with(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line)),
+        parse_line,
+    )
+}
+
+fn parse_line(line_number: usize, line: &str) -> Result<DeckLine<'_>, LineError> {
+    let Some(tags_start) = line.find(" #") else {
+        return deck_list::parse_line(line_number, line);
+    };
+    let mut parsed = deck_list::parse_line(line_number, line[..tags_start].trim_end())?;
+    parsed.line = line;
+
+    // Offset of the `#` before each tag
+    let mut offset = tags_start + 1;
+    for tag in line[offset..].split('#').skip(1) {
+        let start = offset + 1;
+        offset = start + tag.len();
+        let name = tag.strip_prefix('!').unwrap_or(tag);
+        let trimmed = name.trim();
+        if !trimmed.is_empty() {
+            let start = start + (tag.len() - name.len()) + (name.len() - name.trim_start().len());
+            parsed.categories.push(Spanned {
+                value: trimmed,
+                span: Span::from_bytes(line, start, start + trimmed.len()),
+            });
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck_list::{Finish, Section};
+
+    #[test]
+    fn test_parse_moxfield_tags() {
+        let parsed = parse(
+            "1 Sol Ring (C21) 263 *F* #Ramp #!Mana Rock\n1 Opt\n\nSIDEBOARD:\n1 Negate #Counter",
+        );
+        assert!(parsed.errors.is_empty());
+
+        let sol_ring = &parsed.lines[0];
+        assert_eq!(sol_ring.line, "1 Sol Ring (C21) 263 *F* #Ramp #!Mana Rock");
+        assert_eq!(sol_ring.name.value, "Sol Ring");
+        assert_eq!(
+            sol_ring.set_code.map(|set_code| set_code.value),
+            Some("C21")
+        );
+        assert_eq!(sol_ring.finish(), Some(Finish::Foil));
+        let tags: Vec<_> = sol_ring
+            .categories
+            .iter()
+            .map(|tag| (tag.value, tag.span))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("Ramp", Span { start: 26, end: 30 }),
+                ("Mana Rock", Span { start: 33, end: 42 }),
+            ]
+        );
+
+        assert!(parsed.lines[1].categories.is_empty());
+        assert_eq!(parsed.lines[2].section, Section::Sideboard);
+        assert_eq!(parsed.lines[2].categories[0].value, "Counter");
+    }
+}
@@ -0,0 +1,283 @@
+//! Magic Online's deck files.
+//!
+//! `.dek` files are XML with a `Cards` element per card, identified by `CatID`, MTGO's ID for
+//! the printing:
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="utf-8"?>
+//! <Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+//!   <NetDeckID>0</NetDeckID>
+//!   <PreconstructedDeckID>0</PreconstructedDeckID>
+//!   <Cards CatID="31745" Quantity="4" Sideboard="false" Name="Lightning Bolt" Annotation="0" />
+//! </Deck>
+//! ```
+//!
+//! The `.txt` export is `4 Lightning Bolt` lines, with the sideboard after the first blank line.
+
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use super::xml::{Element, ElementError, read_events};
+use super::{add_copies, export_name};
+use crate::deck::DeckResolveResult;
+use crate::deck_list::{
+    CardReference, DeckLine, ParseErrorKind, ParsedDeckList, Section, Spanned, parse_lines,
+};
+
+/// Whether `input` starts like a `.dek` file.
+pub(super) fn is_dek_start(line: &str) -> bool {
+    line.starts_with("<?xml") || line.starts_with("<Deck")
+}
+
+pub(super) fn parse_dek(input: &str) -> ParsedDeckList<'_> {
+    read_events(input, |event, range| match event {
+        Event::Start(element) | Event::Empty(element) if element.name().as_ref() == "Cards" => {
+            Some(read_card(&Element::new(input, range, &element)))
+        }
+        _ => None,
+    })
+}
+
+fn read_card<'a>(element: &Element<'a, '_>) -> Result<DeckLine<'a>, ElementError> {
+    let quantity = element.quantity("Quantity")?;
+    let id = element.attribute("CatID")?.and_then(|id| id.parse().ok());
+    let name = match (element.spanned("Name")?, id) {
+        (Some(name), _) => name.map(Cow::Owned),
+        // The ID alone is enough, written the way deck lines reference MTGO IDs
+        (None, Some(id)) => Spanned {
+            value: Cow::Owned(format!("mtgo:{id}")),
+            span: element.span,
+        },
+        (None, None) => return Err(element.error("Name", ParseErrorKind::EmptyCardName)),
+    };
+    let sideboard = element
+        .attribute("Sideboard")?
+        .is_some_and(|value| value == "true");
+
+    Ok(DeckLine {
+        line_number: element.line_number,
+        line: element.line,
+        section: if sideboard {
+            Section::Sideboard
+        } else {
+            Section::Main
+        },
+        quantity,
+        name,
+        reference: id.map(CardReference::Mtgo),
+        set_code: None,
+        collector_number: None,
+        variant: None,
+        finishes: Vec::new(),
+        categories: Vec::new(),
+    })
+}
+
+pub(super) fn parse_text(input: &str) -> ParsedDeckList<'_> {
+    let mut parsed = parse_lines(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line)),
+    );
+
+    // Without headers, everything after the first blank line is the sideboard, however large
+    let has_headers = input
+        .lines()
+        .any(|line| Section::from_header(line.trim()).is_some());
+    let first_card = parsed.lines.first().map(|line| line.line_number);
+    let sideboard_start = first_card.and_then(|first_card| {
+        input
+            .lines()
+            .enumerate()
+            .skip(first_card)
+            .find(|(_, line)| line.trim().is_empty())
+            .map(|(index, _)| index + 1)
+    });
+    if let Some(sideboard_start) = sideboard_start
+        && !has_headers
+    {
+        for line in &mut parsed.lines {
+            if line.line_number > sideboard_start {
+                line.section = Section::Sideboard;
+            }
+        }
+    }
+    parsed
+}
+
+/// Writes a resolved deck as a `.dek` file. Copies of the same printing are combined, the
+/// commander and companion go in the sideboard as MTGO expects, and the maybeboard is left out.
+pub(super) fn export_dek(deck: &DeckResolveResult) -> String {
+    let mut cards = Vec::new();
+    for entry in &deck.entries {
+        if entry.section == Section::Maybeboard {
+            continue;
+        }
+        let id = entry.card.and_then(|card| match entry.finish {
+            Some(_) => card.mtgo_foil_id.or(card.mtgo_id),
+            None => card.mtgo_id,
+        });
+        // MTGO writes split cards as `Fire/Ice`
+        let name = entry
+            .card
+            .map_or_else(|| entry.name.clone(), |card| export_name(card, "/"));
+        let sideboard = entry.section != Section::Main;
+        add_copies(&mut cards, (id, name, sideboard), entry.quantity);
+    }
+
+    let mut dek = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <Deck xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\" \
+        xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n  \
+        <NetDeckID>0</NetDeckID>\n  \
+        <PreconstructedDeckID>0</PreconstructedDeckID>\n",
+    );
+    for ((id, name, sideboard), quantity) in cards {
+        dek.push_str("  <Cards ");
+        if let Some(id) = id {
+            let _ = write!(dek, "CatID=\"{id}\" ");
+        }
+        let _ = writeln!(
+            dek,
+            "Quantity=\"{quantity}\" Sideboard=\"{sideboard}\" Name=\"{}\" Annotation=\"0\" />",
+            escape(name.as_str())
+        );
+    }
+    dek.push_str("</Deck>\n");
+    dek
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, CardDatabase};
+    use crate::deck::resolve_deck_list;
+    use crate::deck_list::Span;
+    use crate::formats::DeckFormat;
+    use crate::printings::PrintingPreference;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_dek() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <NetDeckID>0</NetDeckID>
+  <PreconstructedDeckID>0</PreconstructedDeckID>
+  <Cards CatID="31745" Quantity="4" Sideboard="false" Name="Lightning Bolt" Annotation="0" />
+  <Cards CatID="12" Quantity="2" Sideboard="true" Annotation="0" />
+  <Cards CatID="13" Quantity="0" Sideboard="false" Name="Opt" Annotation="0" />
+  <Cards CatID="14" Quantity="1" Sideboard="true" Name="Fire/Ice &amp; More" Annotation="0" />
+</Deck>
+"#;
+        let parsed = parse_dek(input);
+
+        let lines: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.line_number,
+                    line.quantity.value,
+                    &*line.name.value,
+                    line.reference,
+                    line.section,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (
+                    5,
+                    4,
+                    "Lightning Bolt",
+                    Some(CardReference::Mtgo(31745)),
+                    Section::Main
+                ),
+                (
+                    6,
+                    2,
+                    "mtgo:12",
+                    Some(CardReference::Mtgo(12)),
+                    Section::Sideboard
+                ),
+                (
+                    8,
+                    1,
+                    "Fire/Ice & More",
+                    Some(CardReference::Mtgo(14)),
+                    Section::Sideboard
+                ),
+            ]
+        );
+        assert_eq!(parsed.lines[0].name.span, Span { start: 58, end: 72 });
+
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 7);
+        assert_eq!(parsed.errors[0].kind, ParseErrorKind::InvalidQuantity);
+        assert_eq!(parsed.errors[0].span, Span { start: 28, end: 29 });
+
+        let errors = parse_dek("<Deck>\n  <Cards Quantity=\"1\" Name=\"Opt\">\n</Deck>").errors;
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ParseErrorKind::InvalidFile { .. }));
+    }
+
+    #[test]
+    fn test_parse_mtgo_text() {
+        let parsed = parse_text("4 Lightning Bolt\n20 Mountain\n\n4 Abrade\n4 Negate\n20 Island\n");
+        let sections: Vec<_> = parsed
+            .lines
+            .iter()
+            .map(|line| (&*line.name.value, line.section))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("Lightning Bolt", Section::Main),
+                ("Mountain", Section::Main),
+                ("Abrade", Section::Sideboard),
+                ("Negate", Section::Sideboard),
+                ("Island", Section::Sideboard),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_dek() {
+        let card = |name: &str, layout: &str, mtgo_id, mtgo_foil_id| Card {
+            name: name.into(),
+            layout: layout.into(),
+            mtgo_id,
+            mtgo_foil_id,
+            ..Default::default()
+        };
+        let cards = Arc::new(CardDatabase::new(vec![
+            card("Lightning Bolt", "normal", Some(100), Some(101)),
+            card("Black Lotus", "normal", None, None),
+        ]));
+        let deck = resolve_deck_list(
+            "2x Lightning Bolt\n1x mtgo:101\n1x Lightning Bolt *F*\n\nSideboard\n\
+                1x Black Lotus\n1x Tom & Jerry\nMaybeboard\n1x Lightning Bolt",
+            DeckFormat::Text,
+            &cards,
+            &PrintingPreference::default(),
+        );
+
+        let dek = export_dek(&deck);
+        assert!(dek.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Deck "));
+        let lines: Vec<_> = dek.lines().filter(|line| line.contains("<Cards")).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "  <Cards CatID=\"100\" Quantity=\"2\" Sideboard=\"false\" Name=\"Lightning Bolt\" Annotation=\"0\" />",
+                "  <Cards CatID=\"101\" Quantity=\"2\" Sideboard=\"false\" Name=\"Lightning Bolt\" Annotation=\"0\" />",
+                "  <Cards Quantity=\"1\" Sideboard=\"true\" Name=\"Black Lotus\" Annotation=\"0\" />",
+                "  <Cards Quantity=\"1\" Sideboard=\"true\" Name=\"Tom &amp; Jerry\" Annotation=\"0\" />",
+            ]
+        );
+        assert_eq!(parse_dek(&dek).lines.len(), 4);
+    }
+}
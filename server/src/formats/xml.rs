@@ -0,0 +1,167 @@
+//! Reading the XML deck files of Magic Online and Cockatrice while keeping track of where each
+//! element is, so problems can point at the line and attribute they are about.
+
+use quick_xml::Reader;
+use quick_xml::XmlVersion;
+use quick_xml::events::{BytesStart, Event};
+use std::ops::Range;
+
+use crate::deck_list::{DeckLine, ParseError, ParseErrorKind, ParsedDeckList, Span, Spanned};
+
+/// A problem with an element and the part of its line it is about.
+pub(super) type ElementError = (Span, ParseErrorKind);
+
+/// Reads `input` event by event, passing each event and the byte range it came from to `handle`,
+/// which returns the card line an event describes, if any. Malformed XML ends the list with an
+/// `invalid_file` error.
+pub(super) fn read_events<'a>(
+    input: &'a str,
+    mut handle: impl FnMut(Event<'a>, Range<usize>) -> Option<Result<DeckLine<'a>, ElementError>>,
+) -> ParsedDeckList<'a> {
+    let mut parsed = ParsedDeckList::default();
+    let mut reader = Reader::from_str(input);
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => {
+                let range = start..reader.buffer_position() as usize;
+                match handle(event, range.clone()) {
+                    Some(Ok(line)) => parsed.lines.push(line),
+                    Some(Err((span, kind))) => {
+                        let (line_number, line, _) = locate(input, range);
+                        parsed
+                            .errors
+                            .push(ParseError::new(line_number, line, span, kind));
+                    }
+                    None => {}
+                }
+            }
+            Err(error) => {
+                let position = reader.error_position() as usize;
+                let (line_number, line, span) = locate(input, position..position);
+                parsed.errors.push(ParseError::new(
+                    line_number,
+                    line,
+                    span,
+                    ParseErrorKind::InvalidFile {
+                        message: error.to_string(),
+                    },
+                ));
+                break;
+            }
+        }
+    }
+    parsed
+}
+
+/// The line number, trimmed line and span in it of the byte range `range` of `input`, cut off
+/// at the end of the line.
+pub(super) fn locate(input: &str, range: Range<usize>) -> (usize, &str, Span) {
+    let start = range.start.min(input.len());
+    let line_start = input[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = input[start..]
+        .find('\n')
+        .map_or(input.len(), |index| start + index);
+    let untrimmed = &input[line_start..line_end];
+    let line = untrimmed.trim();
+    let offset = line_start + (untrimmed.len() - untrimmed.trim_start().len());
+    let span_start = start.clamp(offset, offset + line.len()) - offset;
+    let span_end = range.end.clamp(start, offset + line.len()).max(offset) - offset;
+    let line_number = input[..start].matches('\n').count() + 1;
+    (
+        line_number,
+        line,
+        Span::from_bytes(line, span_start, span_end.max(span_start)),
+    )
+}
+
+/// A start or empty element and where it is in the file.
+pub(super) struct Element<'a, 'e> {
+    input: &'a str,
+    range: Range<usize>,
+    element: &'e BytesStart<'a>,
+    pub line_number: usize,
+    /// The trimmed line the element starts on
+    pub line: &'a str,
+    /// The element in `line`
+    pub span: Span,
+}
+
+impl<'a, 'e> Element<'a, 'e> {
+    pub(super) fn new(input: &'a str, range: Range<usize>, element: &'e BytesStart<'a>) -> Self {
+        let (line_number, line, span) = locate(input, range.clone());
+        Self {
+            input,
+            range,
+            element,
+            line_number,
+            line,
+            span,
+        }
+    }
+
+    /// The byte range of an attribute's value in the file, found in the element's text.
+    fn value_range(&self, key: &str) -> Option<Range<usize>> {
+        let text = &self.input[self.range.clone()];
+        let start = text.find(&format!(" {key}=\""))? + key.len() + 3;
+        let end = start + text[start..].find('"')?;
+        Some(self.range.start + start..self.range.start + end)
+    }
+
+    /// The span of an attribute's value, or of the whole element if the attribute is missing.
+    pub(super) fn value_span(&self, key: &str) -> Span {
+        self.value_range(key)
+            .map_or(self.span, |range| locate(self.input, range).2)
+    }
+
+    /// An attribute's value as written, for values such as set codes that never need escaping.
+    pub(super) fn raw(&self, key: &str) -> Option<Spanned<&'a str>> {
+        let range = self.value_range(key)?;
+        let value = self.input[range.clone()].trim();
+        (!value.is_empty()).then(|| Spanned {
+            value,
+            span: locate(self.input, range).2,
+        })
+    }
+
+    pub(super) fn error(&self, key: &str, kind: ParseErrorKind) -> ElementError {
+        (self.value_span(key), kind)
+    }
+
+    /// The trimmed value of an attribute with escapes replaced.
+    pub(super) fn attribute(&self, key: &str) -> Result<Option<String>, ElementError> {
+        let invalid = |message: String| self.error(key, ParseErrorKind::InvalidFile { message });
+        match self.element.try_get_attribute(key) {
+            Ok(Some(attribute)) => attribute
+                .normalized_value(XmlVersion::Implicit1_0)
+                .map(|value| Some(value.trim().to_string()))
+                .map_err(|e| invalid(e.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(invalid(e.to_string())),
+        }
+    }
+
+    /// A non-empty attribute with its span.
+    pub(super) fn spanned(&self, key: &str) -> Result<Option<Spanned<String>>, ElementError> {
+        Ok(self
+            .attribute(key)?
+            .filter(|value| !value.is_empty())
+            .map(|value| Spanned {
+                value,
+                span: self.value_span(key),
+            }))
+    }
+
+    /// A positive quantity from an attribute.
+    pub(super) fn quantity(&self, key: &str) -> Result<Spanned<u32>, ElementError> {
+        let Some(quantity) = self.spanned(key)? else {
+            return Err(self.error(key, ParseErrorKind::MissingQuantity));
+        };
+        match quantity.value.parse() {
+            Ok(value) if value > 0 => Ok(quantity.map(|_| value)),
+            _ => Err((quantity.span, ParseErrorKind::InvalidQuantity)),
+        }
+    }
+}
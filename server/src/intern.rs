@@ -0,0 +1,177 @@
+//! Shared strings for the card data.
+//!
+//! Most strings in a bulk export repeat across printings: set names, type lines, Oracle text,
+//! keywords and legality formats. Card fields holding such strings are [`IStr`]s, and
+//! [`Interner`] makes equal strings share one allocation when the cards are loaded.
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An immutable, cheaply cloneable string. Serializes as a plain string.
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IStr(Arc<str>);
+
+impl IStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for IStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for IStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for IStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for IStr {
+    fn from(value: &str) -> Self {
+        IStr(value.into())
+    }
+}
+
+impl From<&IStr> for IStr {
+    fn from(value: &IStr) -> Self {
+        value.clone()
+    }
+}
+
+impl From<String> for IStr {
+    fn from(value: String) -> Self {
+        IStr(value.into())
+    }
+}
+
+impl PartialEq<str> for IStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for IStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl fmt::Debug for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl Serialize for IStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for IStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IStrVisitor;
+
+        impl Visitor<'_> for IStrVisitor {
+            type Value = IStr;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<IStr, E> {
+                Ok(IStr::from(value))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<IStr, E> {
+                Ok(IStr::from(value))
+            }
+        }
+
+        deserializer.deserialize_str(IStrVisitor)
+    }
+}
+
+/// Deduplicates strings while cards are loaded. Dropping it keeps the shared strings alive.
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: HashSet<IStr>,
+}
+
+impl Interner {
+    /// Replaces `value` with the shared copy of an equal string, remembering it if it is new.
+    pub fn intern(&mut self, value: &mut IStr) {
+        match self.strings.get(value.as_str()) {
+            Some(shared) => *value = shared.clone(),
+            None => {
+                self.strings.insert(value.clone());
+            }
+        }
+    }
+
+    pub fn intern_option(&mut self, value: &mut Option<IStr>) {
+        if let Some(value) = value {
+            self.intern(value);
+        }
+    }
+
+    pub fn intern_all(&mut self, values: &mut [IStr]) {
+        for value in values {
+            self.intern(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner_shares_equal_strings() {
+        let mut interner = Interner::default();
+        let mut first = IStr::from("Creature");
+        let mut second = IStr::from(String::from("Creature"));
+        let mut other = IStr::from("Instant");
+        assert!(!Arc::ptr_eq(&first.0, &second.0));
+
+        interner.intern(&mut first);
+        interner.intern(&mut second);
+        interner.intern(&mut other);
+
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert!(!Arc::ptr_eq(&first.0, &other.0));
+        assert_eq!(second, "Creature");
+    }
+
+    #[test]
+    fn test_istr_serializes_as_plain_string() {
+        let value = IStr::from("Lightning Bolt");
+        let json = sonic_rs::to_string(&value).unwrap();
+        assert_eq!(json, r#""Lightning Bolt""#);
+        assert_eq!(sonic_rs::from_str::<IStr>(&json).unwrap(), value);
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bincode::deserialize::<IStr>(&bytes).unwrap(), value);
+    }
+}
@@ -1,15 +1,33 @@
+mod batch;
+mod cache;
 mod cards;
+mod config;
 mod deck;
+mod deck_list;
+mod formats;
+mod intern;
+mod names;
+mod printings;
+mod refresh;
+mod search;
 mod server;
+mod sets;
+mod store;
 
-use tracing::{info, level_filters::LevelFilter};
+use clap::Parser;
+use std::sync::Arc;
+use tracing::{error, info, level_filters::LevelFilter};
 use tracing_subscriber::EnvFilter;
 
 use cards::load_cards;
+use config::{Command, Config};
 use server::create_router;
+use store::CardStore;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let config = Config::parse();
+
     tracing_subscriber::fmt()
         .with_env_filter(
             EnvFilter::builder()
@@ -18,8 +36,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .init();
 
-    let cards = load_cards()?;
-    let app = create_router(cards);
+    if let Some(Command::RefreshData(args)) = &config.command {
+        // A file path means the data lives in its directory
+        let data_dir = if config.card_data.is_file() {
+            config.card_data.parent().unwrap_or(&config.card_data)
+        } else {
+            &config.card_data
+        };
+        let path = refresh::refresh_data(data_dir, args).await?;
+        info!(path = %path.display(), "Card data is ready; send SIGHUP to a running server or call POST /admin/reload");
+        return Ok(());
+    }
+
+    let use_cache = !config.no_card_cache;
+    let cards = load_cards(&config.card_data, use_cache)?;
+    let store = Arc::new(CardStore::new(cards, config.card_data.clone(), use_cache));
+    #[cfg(unix)]
+    reload_on_sighup(Arc::clone(&store))?;
+    let admin_enabled = config.admin_token.is_some();
+    let app = create_router(store, config.admin_token);
 
     let address = "127.0.0.1:5678";
     let listener = tokio::net::TcpListener::bind(address).await?;
@@ -28,11 +63,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Available endpoints:");
     info!("  GET  /health           - Health check endpoint");
     info!("  GET  /cards/:name      - Get card by name");
+    info!("  GET  /cards/:name/printings - List printings of a card");
+    info!("  GET  /cards/id/:id     - Get card by Scryfall ID (also /oracle, /mtgo, /arena)");
+    info!("  GET  /cards/autocomplete?q= - Complete a partial card name");
+    info!("  GET  /cards/search?q=  - Search cards with Scryfall-style syntax");
+    info!("  POST /cards/batch      - Look up many cards by name, ID or printing");
+    info!("  GET  /sets             - List sets (also /sets/:code and /sets/:code/cards)");
     info!("  POST /deck/resolve     - Parse and resolve deck list with full card data");
+    info!(
+        "  POST /deck/export      - Resolve a deck list and export it as a .dek, .cod or .dck file"
+    );
     info!("  POST /deck/diff        - Compare two deck lists and show differences");
+    if admin_enabled {
+        info!("  POST /admin/reload     - Reload card data (also on SIGHUP), with the admin token");
+    } else {
+        info!("Send SIGHUP to reload card data; set --admin-token to enable POST /admin/reload");
+    }
     info!("Try: curl http://{address}/cards/Rashmi%20and%20Ragavan");
 
     axum::serve(listener, app).await?;
 
     Ok(())
 }
+
+/// Reloads the card data whenever the process receives SIGHUP.
+#[cfg(unix)]
+fn reload_on_sighup(store: Arc<CardStore>) -> std::io::Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP");
+            let store = Arc::clone(&store);
+            if let Err(join_error) = tokio::task::spawn_blocking(move || store.reload()).await {
+                error!(%join_error, "Card reload task failed");
+            }
+        }
+    });
+    Ok(())
+}
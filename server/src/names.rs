@@ -0,0 +1,348 @@
+use std::collections::{HashMap, HashSet};
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+use crate::intern::IStr;
+
+/// Minimum similarity for a name to be offered as a suggestion.
+const MIN_SUGGESTION_SCORE: f64 = 0.5;
+/// How many of the best trigram matches are scored by edit distance.
+const MAX_CANDIDATES: usize = 200;
+
+/// Normalizes a card name for forgiving lookups.
+///
+/// Names are case folded, accents are stripped (`Lim-Dûl` becomes `lim dul`), apostrophes, quotes
+/// and other punctuation are dropped, and hyphens, slashes and runs of whitespace collapse to a
+/// single space. This makes `Lim-Dul's Vault`, `lim-dûl’s vault` and `LIM DULS VAULT` equal.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut pending_space = false;
+
+    for c in name.nfkd().filter(|&c| !is_combining_mark(c)) {
+        if c.is_alphanumeric() {
+            if pending_space && !normalized.is_empty() {
+                normalized.push(' ');
+            }
+            pending_space = false;
+            match c {
+                'Æ' | 'æ' => normalized.push_str("ae"),
+                'Œ' | 'œ' => normalized.push_str("oe"),
+                _ => normalized.extend(c.to_lowercase()),
+            }
+        } else if c.is_whitespace() || matches!(c, '-' | '‐' | '‑' | '–' | '—' | '/' | '_')
+        {
+            pending_space = true;
+        }
+    }
+
+    normalized
+}
+
+type Trigram = [char; 3];
+
+fn trigrams(normalized: &str) -> impl Iterator<Item = Trigram> {
+    let padded: Vec<char> = format!("  {} ", normalized).chars().collect();
+    (0..padded.len().saturating_sub(2)).map(move |i| [padded[i], padded[i + 1], padded[i + 2]])
+}
+
+/// Prefix and fuzzy search over card names.
+///
+/// Each searchable name (card names and the face names of multi-faced cards) is stored in its
+/// normalized form together with the full card name it resolves to. Prefix queries use a sorted
+/// list of every word start in the names. Fuzzy queries first collect the names sharing the most
+/// trigrams with the query, then rank those by edit distance and word overlap.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    // (normalized searchable name, full card name)
+    entries: Vec<(Box<str>, IStr)>,
+    // (entry id, byte offset of a word start), sorted by the name from that offset
+    word_starts: Vec<(u32, u32)>,
+    trigrams: HashMap<Trigram, Vec<u32>>,
+}
+
+impl NameIndex {
+    pub fn new<'a, N: Into<IStr>>(names: impl IntoIterator<Item = (&'a str, N)>) -> Self {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        let mut trigram_index: HashMap<Trigram, Vec<u32>> = HashMap::new();
+
+        for (searchable, full_name) in names {
+            let normalized = normalize_name(searchable);
+            let full_name = full_name.into();
+            if normalized.is_empty() || !seen.insert((normalized.clone(), full_name.clone())) {
+                continue;
+            }
+
+            let id = entries.len() as u32;
+            for trigram in trigrams(&normalized).collect::<HashSet<_>>() {
+                trigram_index.entry(trigram).or_default().push(id);
+            }
+            entries.push((normalized.into_boxed_str(), full_name));
+        }
+
+        let mut word_starts: Vec<(u32, u32)> = entries
+            .iter()
+            .enumerate()
+            .flat_map(|(id, (normalized, _))| {
+                std::iter::once(0)
+                    .chain(normalized.match_indices(' ').map(|(offset, _)| offset + 1))
+                    .map(move |offset| (id as u32, offset as u32))
+            })
+            .collect();
+        word_starts
+            .sort_unstable_by(|&a, &b| Self::suffix(&entries, a).cmp(Self::suffix(&entries, b)));
+
+        Self {
+            entries,
+            word_starts,
+            trigrams: trigram_index,
+        }
+    }
+
+    fn suffix(entries: &[(Box<str>, IStr)], (id, offset): (u32, u32)) -> &str {
+        &entries[id as usize].0[offset as usize..]
+    }
+
+    /// Completions for a partially typed name, best first.
+    ///
+    /// Names starting with the query come first, then names with a later word starting with it,
+    /// each group ordered by length and then alphabetically. Remaining slots are filled with fuzzy
+    /// matches so typos still produce results.
+    pub fn autocomplete(&self, query: &str, limit: usize) -> Vec<String> {
+        let query = normalize_name(query);
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let start = self.word_starts.partition_point(|&word_start| {
+            Self::suffix(&self.entries, word_start) < query.as_str()
+        });
+        let mut matches: Vec<(bool, usize, &str)> = self.word_starts[start..]
+            .iter()
+            .take_while(|&&word_start| Self::suffix(&self.entries, word_start).starts_with(&query))
+            .map(|&(id, offset)| {
+                let (normalized, full_name) = &self.entries[id as usize];
+                (offset != 0, normalized.len(), full_name.as_str())
+            })
+            .collect();
+        matches.sort_unstable();
+
+        let mut completions = Vec::new();
+        for (_, _, full_name) in matches {
+            push_unique(&mut completions, full_name);
+            if completions.len() == limit {
+                return completions;
+            }
+        }
+
+        for full_name in self.suggest(&query, limit) {
+            push_unique(&mut completions, &full_name);
+            if completions.len() == limit {
+                break;
+            }
+        }
+        completions
+    }
+
+    /// The full names of the cards with a name or face name containing `normalized`, which must
+    /// already be normalized.
+    pub fn names_containing(&self, normalized: &str) -> HashSet<&str> {
+        self.entries
+            .iter()
+            .filter(|(name, _)| name.contains(normalized))
+            .map(|(_, full_name)| full_name.as_str())
+            .collect()
+    }
+
+    /// The full names of the cards closest to `query`, best match first.
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<String> {
+        let query = normalize_name(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut shared_trigrams: HashMap<u32, u32> = HashMap::new();
+        for trigram in trigrams(&query).collect::<HashSet<_>>() {
+            for &id in self.trigrams.get(&trigram).into_iter().flatten() {
+                *shared_trigrams.entry(id).or_default() += 1;
+            }
+        }
+
+        let mut candidates: Vec<(u32, u32)> = shared_trigrams.into_iter().collect();
+        candidates.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        candidates.truncate(MAX_CANDIDATES);
+
+        let mut scored: Vec<(f64, &str)> = candidates
+            .into_iter()
+            .map(|(id, _)| {
+                let (normalized, full_name) = &self.entries[id as usize];
+                (similarity(&query, normalized), full_name.as_str())
+            })
+            .filter(|(score, _)| *score >= MIN_SUGGESTION_SCORE)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(b.1)));
+
+        let mut suggestions = Vec::new();
+        for (_, full_name) in scored {
+            push_unique(&mut suggestions, full_name);
+            if suggestions.len() == limit {
+                break;
+            }
+        }
+        suggestions
+    }
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_string());
+    }
+}
+
+/// Similarity between two normalized names in `0.0..=1.0`, mixing edit distance with how many
+/// words the names have in common. Words are also compared in sorted order so reordered names
+/// like `bolt lightning` still score well.
+fn similarity(a: &str, b: &str) -> f64 {
+    let sorted_words = |name: &str| {
+        let mut words: Vec<&str> = name.split(' ').collect();
+        words.sort_unstable();
+        words.join(" ")
+    };
+    let edit_similarity =
+        edit_similarity(a, b).max(edit_similarity(&sorted_words(a), &sorted_words(b)));
+
+    let a_words: HashSet<&str> = a.split(' ').collect();
+    let b_words: HashSet<&str> = b.split(' ').collect();
+    let word_overlap =
+        a_words.intersection(&b_words).count() as f64 / a_words.union(&b_words).count() as f64;
+
+    0.7 * edit_similarity + 0.3 * word_overlap
+}
+
+fn edit_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len()).max(1);
+    1.0 - levenshtein(&a, &b) as f64 / max_len as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Lightning Bolt"), "lightning bolt");
+        assert_eq!(normalize_name("LIGHTNING  BOLT "), "lightning bolt");
+        assert_eq!(normalize_name("Lim-Dûl's Vault"), "lim duls vault");
+        assert_eq!(normalize_name("Lim-Dul’s Vault"), "lim duls vault");
+        assert_eq!(normalize_name("Séance"), "seance");
+        assert_eq!(normalize_name("Æther Vial"), "aether vial");
+        assert_eq!(normalize_name("Fire // Ice"), "fire ice");
+        assert_eq!(normalize_name("Fire/Ice"), "fire ice");
+        assert_eq!(
+            normalize_name("Aang, at the Crossroads"),
+            "aang at the crossroads"
+        );
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(levenshtein(&chars("bolt"), &chars("bolt")), 0);
+        assert_eq!(
+            levenshtein(&chars("lightning bolt"), &chars("lightnin bolt")),
+            1
+        );
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+    }
+
+    #[test]
+    fn test_suggest_ranks_closest_names_first() {
+        let index = NameIndex::new([
+            ("Lightning Bolt", "Lightning Bolt"),
+            ("Lightning Helix", "Lightning Helix"),
+            ("Chain Lightning", "Chain Lightning"),
+            ("Counterspell", "Counterspell"),
+            ("Bonecrusher Giant", "Bonecrusher Giant // Stomp"),
+            ("Stomp", "Bonecrusher Giant // Stomp"),
+        ]);
+
+        let suggestions = index.suggest("Lightnig Bolt", 3);
+        assert_eq!(suggestions[0], "Lightning Bolt");
+        assert!(!suggestions.contains(&"Counterspell".to_string()));
+
+        assert_eq!(index.suggest("Bolt Lightning", 1), vec!["Lightning Bolt"]);
+        assert_eq!(
+            index.suggest("bonecruser giant", 5),
+            vec!["Bonecrusher Giant // Stomp"]
+        );
+        assert_eq!(index.suggest("Counter spel", 5), vec!["Counterspell"]);
+        assert!(index.suggest("Zzyzx", 5).is_empty());
+    }
+
+    #[test]
+    fn test_autocomplete_ranks_prefixes_before_inner_words() {
+        let index = NameIndex::new([
+            ("Lightning Bolt", "Lightning Bolt"),
+            ("Lightning Helix", "Lightning Helix"),
+            ("Chain Lightning", "Chain Lightning"),
+            ("Light Up the Stage", "Light Up the Stage"),
+            ("Counterspell", "Counterspell"),
+            ("Bonecrusher Giant", "Bonecrusher Giant // Stomp"),
+            ("Stomp", "Bonecrusher Giant // Stomp"),
+        ]);
+
+        assert_eq!(
+            index.autocomplete("light", 10)[..4],
+            [
+                "Lightning Bolt",
+                "Lightning Helix",
+                "Light Up the Stage",
+                "Chain Lightning"
+            ]
+        );
+        assert_eq!(index.autocomplete("LIGHTNING", 2).len(), 2);
+        assert_eq!(
+            index.autocomplete("gian", 5),
+            vec!["Bonecrusher Giant // Stomp"]
+        );
+        assert_eq!(index.autocomplete("countrspell", 5), vec!["Counterspell"]);
+        assert!(index.autocomplete("", 5).is_empty());
+    }
+
+    #[test]
+    fn test_names_containing() {
+        let index = NameIndex::new([
+            ("Lim-Dûl's Vault", "Lim-Dûl's Vault"),
+            ("Bonecrusher Giant", "Bonecrusher Giant // Stomp"),
+            ("Stomp", "Bonecrusher Giant // Stomp"),
+        ]);
+
+        assert_eq!(
+            index.names_containing("duls"),
+            HashSet::from(["Lim-Dûl's Vault"])
+        );
+        assert_eq!(
+            index.names_containing("stomp"),
+            HashSet::from(["Bonecrusher Giant // Stomp"])
+        );
+        assert!(index.names_containing("bolt").is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Choosing between the printings of a card.
+//!
+//! When a deck line names a card without a set, any printing would do. A [`PrintingPreference`]
+//! picks one consistently: filters narrow the printings down (falling back to all printings when
+//! none pass) and an order picks the first of the rest.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use crate::cards::{Card, ImageUris, Prices, Rarity};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrintingOrder {
+    /// Most recent release first
+    #[default]
+    Newest,
+    /// First release first
+    Oldest,
+    /// Lowest USD price first, printings without a price last
+    Cheapest,
+}
+
+/// Which printing to use when a card is named without a specific printing.
+///
+/// Read from the query string of the endpoints that resolve cards, e.g.
+/// `?order=cheapest&non_promo=true&frame=2015`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PrintingPreference {
+    #[serde(default)]
+    pub order: PrintingOrder,
+    /// Skip promotional printings
+    #[serde(default)]
+    pub non_promo: bool,
+    /// Skip full-art printings
+    #[serde(default)]
+    pub non_full_art: bool,
+    /// Only printings with this frame edition, such as `1997` or `2015`
+    pub frame: Option<String>,
+}
+
+impl PrintingPreference {
+    fn accepts(&self, card: &Card) -> bool {
+        !(self.non_promo && card.promo || self.non_full_art && card.full_art)
+            && self
+                .frame
+                .as_deref()
+                .is_none_or(|frame| card.frame.as_deref() == Some(frame))
+    }
+
+    fn compare(&self, a: &Card, b: &Card) -> Ordering {
+        match self.order {
+            PrintingOrder::Newest => b.released_at.cmp(&a.released_at),
+            PrintingOrder::Oldest => a.released_at.cmp(&b.released_at),
+            PrintingOrder::Cheapest => match (usd_price(a), usd_price(b)) {
+                (Some(a_price), Some(b_price)) => a_price.total_cmp(&b_price),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| b.released_at.cmp(&a.released_at)),
+        }
+    }
+
+    /// Sorts printings so the preferred one comes first. Printings passing the filters come
+    /// before the rest.
+    pub fn sort(&self, printings: &mut [&Card]) {
+        printings.sort_by(|a, b| {
+            self.accepts(b)
+                .cmp(&self.accepts(a))
+                .then_with(|| self.compare(a, b))
+        });
+    }
+
+    /// The preferred printing, if there are any printings at all.
+    pub fn choose<'a>(&self, printings: impl IntoIterator<Item = &'a Card>) -> Option<&'a Card> {
+        let mut printings: Vec<&Card> = printings.into_iter().collect();
+        self.sort(&mut printings);
+        printings.first().copied()
+    }
+}
+
+fn usd_price(card: &Card) -> Option<f64> {
+    card.prices.usd.as_deref()?.parse().ok()
+}
+
+/// The printing-level details of a card, as listed by `/cards/:name/printings`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Printing<'a> {
+    pub id: &'a str,
+    pub set: &'a str,
+    pub set_name: &'a str,
+    pub collector_number: &'a str,
+    pub released_at: &'a str,
+    pub lang: &'a str,
+    pub rarity: Rarity,
+    pub finishes: Vec<&'a str>,
+    pub promo: bool,
+    pub full_art: bool,
+    pub frame: Option<&'a str>,
+    pub prices: &'a Prices,
+    pub image_uris: Option<&'a ImageUris>,
+}
+
+impl<'a> From<&'a Card> for Printing<'a> {
+    fn from(card: &'a Card) -> Self {
+        Self {
+            id: &card.id,
+            set: &card.set,
+            set_name: &card.set_name,
+            collector_number: &card.collector_number,
+            released_at: &card.released_at,
+            lang: &card.lang,
+            rarity: card.rarity,
+            finishes: card.finishes.iter().map(|finish| finish.as_str()).collect(),
+            promo: card.promo,
+            full_art: card.full_art,
+            frame: card.frame.as_deref(),
+            prices: &card.prices,
+            image_uris: card.image_uris.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn printing(id: &str, released_at: &str, usd: Option<&str>) -> Card {
+        Card {
+            id: id.to_string(),
+            name: "Sol Ring".into(),
+            released_at: released_at.into(),
+            frame: Some("2015".into()),
+            prices: Prices {
+                usd: usd.map(str::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_choose_printing() {
+        let printings = [
+            Card {
+                promo: true,
+                ..printing("promo", "2024-06-01", Some("0.50"))
+            },
+            Card {
+                frame: Some("1993".into()),
+                ..printing("alpha", "1993-08-05", Some("3000.00"))
+            },
+            printing("c21", "2021-04-23", Some("1.25")),
+            printing("unpriced", "2022-01-01", None),
+        ];
+        let choose =
+            |preference: PrintingPreference| preference.choose(&printings).unwrap().id.as_str();
+
+        assert_eq!(choose(PrintingPreference::default()), "promo");
+        assert_eq!(
+            choose(PrintingPreference {
+                order: PrintingOrder::Oldest,
+                ..Default::default()
+            }),
+            "alpha"
+        );
+        assert_eq!(
+            choose(PrintingPreference {
+                order: PrintingOrder::Cheapest,
+                non_promo: true,
+                ..Default::default()
+            }),
+            "c21"
+        );
+        assert_eq!(
+            choose(PrintingPreference {
+                frame: Some("1993".to_string()),
+                ..Default::default()
+            }),
+            "alpha"
+        );
+        // Filters nothing passes are ignored
+        assert_eq!(
+            choose(PrintingPreference {
+                frame: Some("future".to_string()),
+                order: PrintingOrder::Cheapest,
+                ..Default::default()
+            }),
+            "promo"
+        );
+    }
+}
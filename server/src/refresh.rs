@@ -0,0 +1,299 @@
+//! The `refresh-data` command: downloads a Scryfall bulk data file into the card data directory.
+//!
+//! The bulk data manifest is fetched from `<base url>/bulk-data`, the chosen file is downloaded
+//! next to the existing data as a `.part` file, checked against the size in the manifest and
+//! parsed, and only then renamed into place. Older files of the same bulk type beyond the
+//! retention count are removed together with their cache snapshots. The set list from
+//! `<base url>/sets` is saved alongside as `sets.json` when the server provides one; without it,
+//! cards still load and only unknown set warnings are missing.
+
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::cache::cache_path;
+use crate::cards::Card;
+use crate::config::{BulkType, RefreshArgs};
+use crate::sets::{SETS_FILE_NAME, read_sets_file};
+
+type RefreshError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Deserialize)]
+struct BulkDataList {
+    data: Vec<BulkDataObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkDataObject {
+    #[serde(rename = "type")]
+    bulk_type: String,
+    updated_at: String,
+    download_uri: String,
+    size: u64,
+}
+
+/// File name for a bulk file, matching Scryfall's own naming, e.g. `oracle-cards-20250919090345.json`.
+fn bulk_file_name(bulk_type: BulkType, updated_at: &str) -> Result<String, RefreshError> {
+    let digits: String = updated_at
+        .chars()
+        .filter(char::is_ascii_digit)
+        .take(14)
+        .collect();
+    if digits.len() != 14 {
+        return Err(format!("Unexpected updated_at timestamp: {}", updated_at).into());
+    }
+    Ok(format!(
+        "{}-{}.json",
+        bulk_type.api_name().replace('_', "-"),
+        digits
+    ))
+}
+
+/// Downloads the latest file of the requested bulk type into `data_dir`.
+///
+/// Returns the path of the bulk file, which may already have existed.
+pub async fn refresh_data(data_dir: &Path, args: &RefreshArgs) -> Result<PathBuf, RefreshError> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()?;
+
+    let manifest_url = format!("{}/bulk-data", args.base_url.trim_end_matches('/'));
+    info!(url = %manifest_url, "Fetching bulk data manifest");
+    let manifest = client
+        .get(&manifest_url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let manifest: BulkDataList = sonic_rs::from_slice(&manifest)?;
+
+    let bulk = manifest
+        .data
+        .into_iter()
+        .find(|bulk| bulk.bulk_type == args.bulk_type.api_name())
+        .ok_or_else(|| {
+            format!(
+                "Bulk type {} not found in manifest",
+                args.bulk_type.api_name()
+            )
+        })?;
+
+    fs::create_dir_all(data_dir)?;
+    let target = data_dir.join(bulk_file_name(args.bulk_type, &bulk.updated_at)?);
+
+    if fs::metadata(&target).is_ok_and(|metadata| metadata.len() == bulk.size) {
+        info!(path = %target.display(), "Card data is already up to date");
+    } else {
+        download(&client, &bulk, &target).await?;
+    }
+
+    if let Err(err) = download_sets(&client, &args.base_url, data_dir).await {
+        warn!(error = %err, "Failed to download the set list, keeping the previous one");
+    }
+    prune_old_files(data_dir, args.bulk_type, args.keep as usize)?;
+    Ok(target)
+}
+
+/// Replaces `sets.json` in `data_dir` with the current set list.
+async fn download_sets(
+    client: &reqwest::Client,
+    base_url: &str,
+    data_dir: &Path,
+) -> Result<(), RefreshError> {
+    let url = format!("{}/sets", base_url.trim_end_matches('/'));
+    info!(url = %url, "Downloading set list");
+    let body = client
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let target = data_dir.join(SETS_FILE_NAME);
+    let part = data_dir.join(format!("{}.part", SETS_FILE_NAME));
+    fs::write(&part, &body)?;
+    match read_sets_file(&part) {
+        Ok(sets) => {
+            fs::rename(&part, &target)?;
+            info!(path = %target.display(), set_count = sets.len(), "Saved set list");
+            Ok(())
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&part);
+            Err(format!("Invalid set list: {}", err).into())
+        }
+    }
+}
+
+async fn download(
+    client: &reqwest::Client,
+    bulk: &BulkDataObject,
+    target: &Path,
+) -> Result<(), RefreshError> {
+    let mut part_name = target.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    let part = target.with_file_name(part_name);
+
+    info!(url = %bulk.download_uri, size = bulk.size, "Downloading bulk data");
+    let result = async {
+        let mut response = client
+            .get(&bulk.download_uri)
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut file = tokio::fs::File::create(&part).await?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.sync_all().await?;
+
+        if written != bulk.size {
+            return Err(format!(
+                "Downloaded {} bytes but the manifest lists {}",
+                written, bulk.size
+            )
+            .into());
+        }
+
+        let verify_path = part.clone();
+        let card_count = tokio::task::spawn_blocking(move || {
+            let cards: Vec<Card> = sonic_rs::from_reader(BufReader::new(File::open(verify_path)?))?;
+            Ok::<_, RefreshError>(cards.len())
+        })
+        .await??;
+        if card_count == 0 {
+            return Err("Downloaded bulk data contains no cards".into());
+        }
+
+        fs::rename(&part, target)?;
+        info!(path = %target.display(), card_count, "Saved bulk data");
+        Ok(())
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result
+}
+
+/// Removes all but the `keep` newest files of one bulk type, including their cache snapshots.
+fn prune_old_files(data_dir: &Path, bulk_type: BulkType, keep: usize) -> std::io::Result<()> {
+    let prefix = format!("{}-", bulk_type.api_name().replace('_', "-"));
+    let mut files: Vec<PathBuf> = fs::read_dir(data_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".json"))
+        })
+        .collect();
+
+    // Timestamps are fixed width, so the newest file sorts last
+    files.sort();
+    let remove_count = files.len().saturating_sub(keep);
+    for old in &files[..remove_count] {
+        info!(path = %old.display(), "Removing old bulk data");
+        fs::remove_file(old)?;
+        let cache = cache_path(old);
+        if cache.exists() {
+            fs::remove_file(cache)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+
+    const CARDS_JSON: &str = r#"[{"id": "forest", "name": "Forest", "lang": "en",
+        "released_at": "2020-01-01", "layout": "normal", "rarity": "common", "set": "tst",
+        "set_name": "Test", "set_type": "core", "collector_number": "1", "scryfall_uri": "",
+        "image_status": "missing"}]"#;
+
+    const SETS_JSON: &str = r#"{"object": "list", "has_more": false, "data": [{"object": "set",
+        "code": "tst", "name": "Test", "set_type": "core", "released_at": "2020-01-01",
+        "card_count": 1, "digital": false}]}"#;
+
+    /// Serves a manifest and bulk file like Scryfall does, returning the base URL.
+    async fn start_fixture_server(listed_size: usize) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let manifest = format!(
+            r#"{{"object": "list", "data": [{{"type": "oracle_cards",
+            "updated_at": "2025-10-01T09:00:00.593+00:00",
+            "download_uri": "{base_url}/files/oracle-cards.json", "size": {listed_size}}}]}}"#
+        );
+        let app = Router::new()
+            .route("/bulk-data", get(move || async move { manifest }))
+            .route("/files/oracle-cards.json", get(|| async { CARDS_JSON }))
+            .route("/sets", get(|| async { SETS_JSON }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        base_url
+    }
+
+    fn refresh_args(base_url: String) -> RefreshArgs {
+        RefreshArgs {
+            bulk_type: BulkType::OracleCards,
+            base_url,
+            keep: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_data_downloads_and_prunes() {
+        let dir = std::env::temp_dir().join(format!("mtg-refresh-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for old in [
+            "oracle-cards-20240101000000.json",
+            "oracle-cards-20250101000000.json",
+            "default-cards-20230101000000.json",
+        ] {
+            fs::write(dir.join(old), "[]").unwrap();
+        }
+        fs::write(dir.join("oracle-cards-20240101000000.json.cache"), "").unwrap();
+
+        let base_url = start_fixture_server(CARDS_JSON.len()).await;
+        let path = refresh_data(&dir, &refresh_args(base_url)).await.unwrap();
+
+        assert_eq!(path, dir.join("oracle-cards-20251001090000.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), CARDS_JSON);
+        assert!(dir.join("oracle-cards-20250101000000.json").exists());
+        assert!(!dir.join("oracle-cards-20240101000000.json").exists());
+        assert!(!dir.join("oracle-cards-20240101000000.json.cache").exists());
+        assert!(dir.join("default-cards-20230101000000.json").exists());
+        let sets = read_sets_file(&dir.join(SETS_FILE_NAME)).unwrap();
+        assert_eq!(sets[0].name, "Test");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_data_rejects_size_mismatch() {
+        let dir = std::env::temp_dir().join(format!("mtg-refresh-bad-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let base_url = start_fixture_server(CARDS_JSON.len() + 1).await;
+        assert!(refresh_data(&dir, &refresh_args(base_url)).await.is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,823 @@
+//! A practical subset of Scryfall's search syntax, evaluated against the loaded cards.
+//!
+//! Supported filters:
+//!
+//! - bare words and `"quoted phrases"` match card names, `!"Exact Name"` matches a name exactly
+//! - `t:` / `type:` type line, `o:` / `oracle:` rules text
+//! - `c:` / `color:` and `id:` / `identity:` colors, e.g. `c:rg`, `c>=wu`, `id:wub`
+//! - `mv` / `cmc` / `manavalue` comparisons, e.g. `mv>=3`
+//! - `r:` / `rarity:` with comparisons, e.g. `r>=rare`
+//! - `s:` / `set:` / `e:` / `edition:` set code
+//! - `f:` / `format:`, `banned:`, `restricted:` format legality
+//! - `pow` / `power`, `tou` / `toughness` comparisons against numbers or other stats, e.g. `pow>tou`
+//!
+//! Terms are combined with `and` (also implied by whitespace) and `or`, negated with a leading
+//! `-`, and grouped with parentheses.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::cards::{Card, CardMap, Color, Legality, Rarity};
+use crate::names::normalize_name;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    pub message: String,
+    /// Byte offset into the query where the problem was found
+    pub position: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn compare<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::NotEq => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+
+    /// Compares two color sets, where "greater" means "strict superset".
+    fn compare_colors(self, left: ColorSet, right: ColorSet) -> bool {
+        let subset = left.0 & !right.0 == 0;
+        let superset = right.0 & !left.0 == 0;
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::NotEq => left != right,
+            Comparison::Lt => subset && left != right,
+            Comparison::Le => subset,
+            Comparison::Gt => superset && left != right,
+            Comparison::Ge => superset,
+        }
+    }
+}
+
+/// A set of colors as a bit mask in WUBRG order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorSet(u8);
+
+impl ColorSet {
+    fn from_colors<'a>(colors: impl IntoIterator<Item = &'a Color>) -> Self {
+        ColorSet(colors.into_iter().fold(0, |mask, color| {
+            mask | match color {
+                Color::W => 1,
+                Color::U => 2,
+                Color::B => 4,
+                Color::R => 8,
+                Color::G => 16,
+            }
+        }))
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let named = match value {
+            "white" => Some(ColorSet(1)),
+            "blue" => Some(ColorSet(2)),
+            "black" => Some(ColorSet(4)),
+            "red" => Some(ColorSet(8)),
+            "green" => Some(ColorSet(16)),
+            "colorless" => Some(ColorSet(0)),
+            _ => None,
+        };
+        if named.is_some() {
+            return named;
+        }
+
+        value.chars().try_fold(ColorSet(0), |set, c| {
+            let bit = match c {
+                'w' => 1,
+                'u' => 2,
+                'b' => 4,
+                'r' => 8,
+                'g' => 16,
+                'c' => 0,
+                _ => return None,
+            };
+            Some(ColorSet(set.0 | bit))
+        })
+    }
+}
+
+/// The right-hand side of a power or toughness comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatValue {
+    Number(f64),
+    Power,
+    Toughness,
+    ManaValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Name(String),
+    ExactName(String),
+    Type(String),
+    Oracle(String),
+    Colors(Comparison, ColorSet),
+    Identity(Comparison, ColorSet),
+    ManaValue(Comparison, f64),
+    Rarity(Comparison, Rarity),
+    Set(String),
+    Legal(String),
+    Banned(String),
+    Restricted(String),
+    Power(Comparison, StatValue),
+    Toughness(Comparison, StatValue),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Filter(Filter),
+}
+
+/// The card names matching each name term of a query, looked up once per search in the
+/// normalized names of the name index.
+type NameMatches<'a> = HashMap<&'a str, HashSet<&'a str>>;
+
+impl Expr {
+    pub fn matches(&self, card: &Card, names: &NameMatches) -> bool {
+        match self {
+            Expr::And(exprs) => exprs.iter().all(|expr| expr.matches(card, names)),
+            Expr::Or(exprs) => exprs.iter().any(|expr| expr.matches(card, names)),
+            Expr::Not(expr) => !expr.matches(card, names),
+            Expr::Filter(filter) => filter.matches(card, names),
+        }
+    }
+
+    /// Looks up the card names matching every name term in the expression.
+    fn find_names<'a>(&'a self, cards: &'a CardMap, names: &mut NameMatches<'a>) {
+        match self {
+            Expr::And(exprs) | Expr::Or(exprs) => {
+                for expr in exprs {
+                    expr.find_names(cards, names);
+                }
+            }
+            Expr::Not(expr) => expr.find_names(cards, names),
+            Expr::Filter(Filter::Name(name)) => {
+                names
+                    .entry(name.as_str())
+                    .or_insert_with(|| cards.names_containing(name));
+            }
+            Expr::Filter(_) => {}
+        }
+    }
+}
+
+impl Filter {
+    fn matches(&self, card: &Card, names: &NameMatches) -> bool {
+        match self {
+            Filter::Name(name) => names
+                .get(name.as_str())
+                .is_some_and(|matches| matches.contains(card.name.as_str())),
+            Filter::ExactName(name) => {
+                card.name.eq_ignore_ascii_case(name)
+                    || card
+                        .face_names()
+                        .any(|face| face.eq_ignore_ascii_case(name))
+            }
+            Filter::Type(text) => type_lines(card).any(|line| contains_ignore_case(line, text)),
+            Filter::Oracle(text) => oracle_texts(card).any(|line| contains_ignore_case(line, text)),
+            Filter::Colors(comparison, colors) => {
+                comparison.compare_colors(card_colors(card), *colors)
+            }
+            Filter::Identity(comparison, colors) => {
+                comparison.compare_colors(ColorSet::from_colors(&card.color_identity), *colors)
+            }
+            Filter::ManaValue(comparison, value) => {
+                card.cmc.is_some_and(|cmc| comparison.compare(cmc, *value))
+            }
+            Filter::Rarity(comparison, rarity) => {
+                comparison.compare(rarity_rank(card.rarity), rarity_rank(*rarity))
+            }
+            Filter::Set(set) => card.set.eq_ignore_ascii_case(set),
+            Filter::Legal(format) => matches!(
+                card.legalities.get(format.as_str()),
+                Some(Legality::Legal | Legality::Restricted)
+            ),
+            Filter::Banned(format) => {
+                card.legalities.get(format.as_str()) == Some(&Legality::Banned)
+            }
+            Filter::Restricted(format) => {
+                card.legalities.get(format.as_str()) == Some(&Legality::Restricted)
+            }
+            Filter::Power(comparison, value) => {
+                compare_stat(card, StatValue::Power, *comparison, *value)
+            }
+            Filter::Toughness(comparison, value) => {
+                compare_stat(card, StatValue::Toughness, *comparison, *value)
+            }
+        }
+    }
+}
+
+/// Whether `haystack` contains the already lowercase `needle`, ignoring case, without allocating.
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    if haystack.is_ascii() && needle.is_ascii() {
+        return haystack
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()));
+    }
+    haystack.char_indices().any(|(start, _)| {
+        let mut lowercase = haystack[start..].chars().flat_map(char::to_lowercase);
+        needle.chars().all(|c| lowercase.next() == Some(c))
+    })
+}
+
+fn type_lines(card: &Card) -> impl Iterator<Item = &str> {
+    card.type_line.as_deref().into_iter().chain(
+        card.card_faces
+            .iter()
+            .flatten()
+            .filter_map(|face| face.type_line.as_deref()),
+    )
+}
+
+fn oracle_texts(card: &Card) -> impl Iterator<Item = &str> {
+    card.oracle_text.as_deref().into_iter().chain(
+        card.card_faces
+            .iter()
+            .flatten()
+            .filter_map(|face| face.oracle_text.as_deref()),
+    )
+}
+
+/// Colors of the card, or of all its faces combined when the card itself has none listed.
+fn card_colors(card: &Card) -> ColorSet {
+    match &card.colors {
+        Some(colors) => ColorSet::from_colors(colors),
+        None => ColorSet::from_colors(
+            card.card_faces
+                .iter()
+                .flatten()
+                .flat_map(|face| face.colors.iter().flatten()),
+        ),
+    }
+}
+
+fn rarity_rank(rarity: Rarity) -> u8 {
+    match rarity {
+        Rarity::Common => 0,
+        Rarity::Uncommon => 1,
+        Rarity::Rare => 2,
+        Rarity::Special => 3,
+        Rarity::Mythic => 4,
+        Rarity::Bonus => 5,
+    }
+}
+
+/// Numeric value of a stat. `*` counts as zero, like on Scryfall, so `1+*` is 1.
+fn stat(card: &Card, stat: StatValue) -> Option<f64> {
+    let front = card.card_faces.as_ref().and_then(|faces| faces.first());
+    let text = match stat {
+        StatValue::Number(value) => return Some(value),
+        StatValue::ManaValue => return card.cmc,
+        StatValue::Power => card
+            .power
+            .as_deref()
+            .or_else(|| front.and_then(|face| face.power.as_deref())),
+        StatValue::Toughness => card
+            .toughness
+            .as_deref()
+            .or_else(|| front.and_then(|face| face.toughness.as_deref())),
+    }?;
+
+    let numeric: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '.'))
+        .collect();
+    match numeric.as_str() {
+        "" if text.starts_with('*') => Some(0.0),
+        _ => numeric.parse().ok(),
+    }
+}
+
+fn compare_stat(card: &Card, left: StatValue, comparison: Comparison, right: StatValue) -> bool {
+    match (stat(card, left), stat(card, right)) {
+        (Some(left), Some(right)) => comparison.compare(left, right),
+        _ => false,
+    }
+}
+
+/// Parses a search query into an expression tree.
+pub fn parse_query(query: &str) -> Result<Expr, QueryError> {
+    let mut parser = Parser {
+        input: query,
+        pos: 0,
+    };
+    let expr = parser.parse_or()?;
+    parser.skip_whitespace();
+    if parser.pos < query.len() {
+        return Err(parser.error("Unexpected ')'"));
+    }
+    Ok(expr)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> QueryError {
+        QueryError {
+            message: message.into(),
+            position: self.pos,
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `keyword` if it is the next whole word, ignoring case.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let rest = self.rest();
+        let matches = rest
+            .get(..keyword.len())
+            .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+            && rest[keyword.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || c == '(');
+        if matches {
+            self.pos += keyword.len();
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = vec![self.parse_and()?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat_keyword("or") {
+                break;
+            }
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some(')') => break,
+                _ => {}
+            }
+            if !exprs.is_empty() {
+                let start = self.pos;
+                if self.eat_keyword("or") {
+                    self.pos = start;
+                    break;
+                }
+                if self.eat_keyword("and") {
+                    continue;
+                }
+            }
+            exprs.push(self.parse_unary()?);
+        }
+
+        match exprs.len() {
+            0 => Err(self.error("Expected a search term")),
+            1 => Ok(exprs.remove(0)),
+            _ => Ok(Expr::And(exprs)),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            self.skip_whitespace();
+            if self.peek() != Some(')') {
+                return Err(self.error("Expected ')'"));
+            }
+            self.pos += 1;
+            return Ok(expr);
+        }
+        self.parse_term().map(Expr::Filter)
+    }
+
+    /// Reads a quoted string or a bare word ending at whitespace or a parenthesis.
+    fn parse_value(&mut self) -> Result<String, QueryError> {
+        let input = self.input;
+        if self.peek() == Some('"') {
+            let start = self.pos;
+            let rest = &input[start + 1..];
+            let Some(end) = rest.find('"') else {
+                return Err(self.error("Unterminated quote"));
+            };
+            self.pos = start + end + 2;
+            return Ok(rest[..end].to_string());
+        }
+
+        let rest = &input[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .unwrap_or(rest.len());
+        self.pos += end;
+        Ok(rest[..end].to_string())
+    }
+
+    fn parse_term(&mut self) -> Result<Filter, QueryError> {
+        let start = self.pos;
+
+        if self.peek() == Some('!') {
+            self.pos += 1;
+            let name = self.parse_value()?;
+            if name.is_empty() {
+                return Err(self.error("Expected a card name after '!'"));
+            }
+            return Ok(Filter::ExactName(name));
+        }
+
+        let rest = self.rest();
+        let key_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let operator = [
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("!=", Comparison::NotEq),
+            (":", Comparison::Eq),
+            ("=", Comparison::Eq),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .into_iter()
+        .find(|(symbol, _)| key_len > 0 && rest[key_len..].starts_with(symbol));
+
+        let Some((symbol, comparison)) = operator else {
+            let word = self.parse_value()?;
+            if word.is_empty() {
+                return Err(self.error("Expected a search term"));
+            }
+            return Ok(Filter::Name(normalize_name(&word)));
+        };
+
+        let key = rest[..key_len].to_lowercase();
+        let is_colon = symbol == ":";
+        self.pos += key_len + symbol.len();
+        let value_start = self.pos;
+        let value = self.parse_value()?;
+        if value.is_empty() {
+            return Err(self.error(format!("Expected a value for '{}'", key)));
+        }
+        let lower = value.to_lowercase();
+        let invalid = |message: String| QueryError {
+            message,
+            position: value_start,
+        };
+
+        let require_colon = |filter: Filter| {
+            if is_colon {
+                Ok(filter)
+            } else {
+                Err(QueryError {
+                    message: format!("'{}' only supports ':'", key),
+                    position: start,
+                })
+            }
+        };
+
+        match key.as_str() {
+            "t" | "type" => require_colon(Filter::Type(lower)),
+            "o" | "oracle" => require_colon(Filter::Oracle(lower)),
+            "s" | "set" | "e" | "edition" => require_colon(Filter::Set(lower)),
+            "f" | "format" | "legal" => require_colon(Filter::Legal(lower)),
+            "banned" => require_colon(Filter::Banned(lower)),
+            "restricted" => require_colon(Filter::Restricted(lower)),
+            "c" | "color" | "id" | "identity" | "ci" => {
+                let colors = ColorSet::parse(&lower)
+                    .ok_or_else(|| invalid(format!("Unknown colors '{}'", value)))?;
+                let is_identity = matches!(key.as_str(), "id" | "identity" | "ci");
+                // Like Scryfall, `c:` means "at least these colors" and `id:` means "fits in
+                // this identity". Colorless is always an exact match.
+                let comparison = match (is_colon, is_identity) {
+                    (true, _) if colors == ColorSet(0) => Comparison::Eq,
+                    (true, false) => Comparison::Ge,
+                    (true, true) => Comparison::Le,
+                    (false, _) => comparison,
+                };
+                Ok(if is_identity {
+                    Filter::Identity(comparison, colors)
+                } else {
+                    Filter::Colors(comparison, colors)
+                })
+            }
+            "mv" | "cmc" | "manavalue" => lower
+                .parse()
+                .map(|value| Filter::ManaValue(comparison, value))
+                .map_err(|_| invalid(format!("Expected a number, found '{}'", value))),
+            "r" | "rarity" => {
+                let rarity = match lower.as_str() {
+                    "c" | "common" => Rarity::Common,
+                    "u" | "uncommon" => Rarity::Uncommon,
+                    "r" | "rare" => Rarity::Rare,
+                    "s" | "special" => Rarity::Special,
+                    "m" | "mythic" => Rarity::Mythic,
+                    "b" | "bonus" => Rarity::Bonus,
+                    _ => return Err(invalid(format!("Unknown rarity '{}'", value))),
+                };
+                Ok(Filter::Rarity(comparison, rarity))
+            }
+            "pow" | "power" | "tou" | "toughness" => {
+                let stat = match lower.as_str() {
+                    "pow" | "power" => StatValue::Power,
+                    "tou" | "toughness" => StatValue::Toughness,
+                    "mv" | "cmc" | "manavalue" => StatValue::ManaValue,
+                    _ => StatValue::Number(lower.parse().map_err(|_| {
+                        invalid(format!("Expected a number or stat, found '{}'", value))
+                    })?),
+                };
+                Ok(if key.starts_with('p') {
+                    Filter::Power(comparison, stat)
+                } else {
+                    Filter::Toughness(comparison, stat)
+                })
+            }
+            _ => Err(QueryError {
+                message: format!("Unknown search keyword '{}'", key),
+                position: start,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult<'a> {
+    pub total_cards: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub has_more: bool,
+    pub data: Vec<&'a Card>,
+}
+
+impl<'a> SearchResult<'a> {
+    /// Takes one page out of all matches, counting pages from 1.
+    pub fn paginate(matches: &[&'a Card], page: usize, page_size: usize) -> Self {
+        let page = page.max(1);
+        let start = (page - 1).saturating_mul(page_size);
+        let data: Vec<&Card> = matches
+            .iter()
+            .skip(start)
+            .take(page_size)
+            .copied()
+            .collect();
+
+        SearchResult {
+            total_cards: matches.len(),
+            page,
+            page_size,
+            has_more: start + data.len() < matches.len(),
+            data,
+        }
+    }
+}
+
+/// Searches the loaded cards, returning one page of matches sorted by name.
+///
+/// Every printing is considered, so set filters find older printings, but each card name is
+/// returned once using its most recent matching printing.
+pub fn search_cards<'a>(
+    cards: &'a CardMap,
+    query: &str,
+    page: usize,
+    page_size: usize,
+) -> Result<SearchResult<'a>, QueryError> {
+    let expr = parse_query(query)?;
+    let mut names = NameMatches::new();
+    expr.find_names(cards, &mut names);
+
+    let mut matches: Vec<&Card> = cards
+        .names()
+        .filter_map(|name| {
+            cards
+                .printings(name)
+                .find(|card| expr.matches(card, &names))
+        })
+        .collect();
+    matches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(SearchResult::paginate(&matches, page, page_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::CardDatabase;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    fn create_test_cards() -> CardMap {
+        let card = |name: &str, type_line: &str, mana_value: f64, colors: Vec<Color>| Card {
+            id: name.to_lowercase(),
+            name: name.into(),
+            type_line: Some(type_line.into()),
+            cmc: Some(mana_value),
+            color_identity: colors.clone(),
+            colors: Some(colors),
+            ..Default::default()
+        };
+
+        Arc::new(CardDatabase::new(vec![
+            Card {
+                oracle_text: Some("Lightning Bolt deals 3 damage to any target.".into()),
+                set: "m11".into(),
+                legalities: BTreeMap::from([("modern".into(), Legality::Legal)]),
+                ..card("Lightning Bolt", "Instant", 1.0, vec![Color::R])
+            },
+            Card {
+                oracle_text: Some("Counter target spell.".into()),
+                rarity: Rarity::Uncommon,
+                set: "dsc".into(),
+                ..card("Counterspell", "Instant", 2.0, vec![Color::U])
+            },
+            Card {
+                power: Some("1".into()),
+                toughness: Some("1".into()),
+                ..card(
+                    "Llanowar Elves",
+                    "Creature — Elf Druid",
+                    1.0,
+                    vec![Color::G],
+                )
+            },
+            Card {
+                power: Some("*".into()),
+                toughness: Some("4".into()),
+                rarity: Rarity::Mythic,
+                ..card("Tarmogoyf", "Creature — Lhurgoyf", 2.0, vec![Color::G])
+            },
+            Card {
+                legalities: BTreeMap::from([
+                    ("modern".into(), Legality::Banned),
+                    ("vintage".into(), Legality::Restricted),
+                ]),
+                ..card("Sol Ring", "Artifact", 1.0, vec![])
+            },
+            Card {
+                rarity: Rarity::Rare,
+                ..card("Lightning Helix", "Instant", 2.0, vec![Color::R, Color::W])
+            },
+        ]))
+    }
+
+    fn search_names(cards: &CardMap, query: &str) -> Vec<String> {
+        search_cards(cards, query, 1, 100)
+            .unwrap()
+            .data
+            .into_iter()
+            .map(|card| card.name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_query_structure() {
+        assert_eq!(
+            parse_query("t:instant -c:r").unwrap(),
+            Expr::And(vec![
+                Expr::Filter(Filter::Type("instant".to_string())),
+                Expr::Not(Box::new(Expr::Filter(Filter::Colors(
+                    Comparison::Ge,
+                    ColorSet(8)
+                )))),
+            ])
+        );
+        assert_eq!(
+            parse_query("mv>=3 or (pow>tou)").unwrap(),
+            Expr::Or(vec![
+                Expr::Filter(Filter::ManaValue(Comparison::Ge, 3.0)),
+                Expr::Filter(Filter::Power(Comparison::Gt, StatValue::Toughness)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert_eq!(parse_query("(t:instant").unwrap_err().position, 10);
+        assert_eq!(parse_query("t:instant)").unwrap_err().position, 9);
+        assert_eq!(parse_query("mv>=three").unwrap_err().position, 4);
+        assert!(parse_query("foo:bar").is_err());
+        assert!(parse_query("o:\"unterminated").is_err());
+        assert!(parse_query("t>instant").is_err());
+        assert!(parse_query("").is_err());
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let cards = create_test_cards();
+
+        assert_eq!(
+            search_names(&cards, "t:instant"),
+            vec!["Counterspell", "Lightning Bolt", "Lightning Helix"]
+        );
+        assert_eq!(
+            search_names(&cards, "o:\"3 damage\""),
+            vec!["Lightning Bolt"]
+        );
+        assert_eq!(
+            search_names(&cards, "c:r"),
+            vec!["Lightning Bolt", "Lightning Helix"]
+        );
+        assert_eq!(search_names(&cards, "c=r"), vec!["Lightning Bolt"]);
+        assert_eq!(search_names(&cards, "c:c"), vec!["Sol Ring"]);
+        assert_eq!(
+            search_names(&cards, "id:rw t:instant"),
+            vec!["Lightning Bolt", "Lightning Helix"]
+        );
+        assert_eq!(
+            search_names(&cards, "mv>1 -t:creature"),
+            vec!["Counterspell", "Lightning Helix"]
+        );
+        assert_eq!(
+            search_names(&cards, "r>=rare"),
+            vec!["Lightning Helix", "Tarmogoyf"]
+        );
+        assert_eq!(search_names(&cards, "s:DSC"), vec!["Counterspell"]);
+        assert_eq!(search_names(&cards, "f:modern"), vec!["Lightning Bolt"]);
+        assert_eq!(search_names(&cards, "f:vintage"), vec!["Sol Ring"]);
+        assert_eq!(search_names(&cards, "banned:modern"), vec!["Sol Ring"]);
+        assert_eq!(search_names(&cards, "pow<tou"), vec!["Tarmogoyf"]);
+        assert_eq!(search_names(&cards, "tou>=4"), vec!["Tarmogoyf"]);
+        assert_eq!(
+            search_names(&cards, "lightning -helix"),
+            vec!["Lightning Bolt"]
+        );
+        assert_eq!(search_names(&cards, "!\"sol ring\""), vec!["Sol Ring"]);
+        assert_eq!(
+            search_names(&cards, "(c:u or c:g) and t:creature"),
+            vec!["Llanowar Elves", "Tarmogoyf"]
+        );
+    }
+
+    #[test]
+    fn test_contains_ignore_case() {
+        assert!(contains_ignore_case("Legendary Creature — Elf", "creature"));
+        assert!(contains_ignore_case("Legendary Creature — Elf", "— elf"));
+        assert!(contains_ignore_case("ÆTHER VIAL", "æther"));
+        assert!(contains_ignore_case("Counter target spell.", ""));
+        assert!(!contains_ignore_case("Instant", "instants"));
+        assert!(!contains_ignore_case("Séance", "seance"));
+    }
+
+    #[test]
+    fn test_search_pagination() {
+        let cards = create_test_cards();
+
+        let first = search_cards(&cards, "mv<=2", 1, 4).unwrap();
+        assert_eq!(first.total_cards, 6);
+        assert_eq!(first.data.len(), 4);
+        assert!(first.has_more);
+
+        let second = search_cards(&cards, "mv<=2", 2, 4).unwrap();
+        assert_eq!(second.data.len(), 2);
+        assert_eq!(second.data[0].name, "Sol Ring");
+        assert!(!second.has_more);
+    }
+}
@@ -1,45 +1,72 @@
 use axum::{
     Router,
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Query, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
-use tracing::{debug, instrument, warn};
+use tracing::{debug, error, instrument, warn};
 
-use crate::cards::{Card, CardMap, get_card_by_name};
-use crate::deck::{DeckResolveResult, resolve_deck_list, DeckDiffRequest, DeckDiffResult, diff_decks};
+use crate::batch::{BatchRequest, MAX_BATCH_SIZE, lookup_cards};
+use crate::cards::{Card, CardDataSource, get_card_by_name};
+use crate::deck::{DeckDiffRequest, diff_decks, resolve_deck_list};
+use crate::formats::{ExportQuery, FormatQuery};
+use crate::printings::{Printing, PrintingPreference};
+use crate::search::{SearchResult, search_cards};
+use crate::store::CardStore;
 
 #[derive(Serialize)]
 pub struct HealthResponse {
     status: String,
     version: String,
     cards_loaded: usize,
+    card_data: Option<CardDataSource>,
+    /// Resident memory of the server process, `None` where it cannot be read
+    memory_bytes: Option<u64>,
 }
 
-#[instrument(skip(cards))]
-pub async fn health_check_handler(
-    State(cards): State<CardMap>,
-) -> Json<HealthResponse> {
+/// Reads the resident set size from `/proc`, which only exists on Linux.
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[instrument(skip(store))]
+pub async fn health_check_handler(State(store): State<Arc<CardStore>>) -> Json<HealthResponse> {
+    let cards = store.current();
     Json(HealthResponse {
         status: "healthy".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         cards_loaded: cards.len(),
+        card_data: cards.source().cloned(),
+        memory_bytes: resident_memory_bytes(),
     })
 }
 
-#[instrument(skip(cards))]
+#[instrument(skip(store))]
 pub async fn get_card_by_name_handler(
-    State(cards): State<CardMap>,
+    State(store): State<Arc<CardStore>>,
     Path(name): Path<String>,
-) -> Result<Json<Card>, StatusCode> {
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
     debug!("Fetching card");
     match get_card_by_name(&cards, &name) {
         Some(card) => {
             debug!(card_name = %name, "Card found");
-            Ok(Json(card.clone()))
+            // Serialized while the snapshot is held, so the card is never copied
+            Ok(Json(card).into_response())
         }
         None => {
             warn!(card_name = %name, "Card not found");
@@ -48,27 +75,288 @@ pub async fn get_card_by_name_handler(
     }
 }
 
+#[derive(Serialize)]
+pub struct PrintingsResponse<'a> {
+    name: &'a str,
+    printings: Vec<Printing<'a>>,
+}
+
+/// Lists the printings of a card, the one `preference` would pick first.
+#[instrument(skip(store))]
+pub async fn get_card_printings_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(name): Path<String>,
+    Query(preference): Query<PrintingPreference>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    let mut printings: Vec<_> = cards.printings(&name).collect();
+    let Some(first) = printings.first() else {
+        warn!(card_name = %name, "Card not found");
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let name = first.name.as_str();
+
+    preference.sort(&mut printings);
+    debug!(card_name = %name, printings = printings.len(), "Printings found");
+    Ok(Json(PrintingsResponse {
+        name,
+        printings: printings.into_iter().map(Printing::from).collect(),
+    })
+    .into_response())
+}
+
+/// Serializes a card found by one of the ID lookups, or 404.
+fn card_by_id_response(card: Option<&Card>, id: &str) -> Result<Response, StatusCode> {
+    match card {
+        Some(card) => {
+            debug!(id, card_name = %card.name, "Card found");
+            Ok(Json(card).into_response())
+        }
+        None => {
+            warn!(id, "Card not found");
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+#[instrument(skip(store))]
+pub async fn get_card_by_scryfall_id_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    card_by_id_response(cards.get_by_scryfall_id(&id), &id)
+}
+
+#[instrument(skip(store))]
+pub async fn get_card_by_oracle_id_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(oracle_id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    card_by_id_response(cards.get_by_oracle_id(&oracle_id), &oracle_id)
+}
+
+#[instrument(skip(store))]
+pub async fn get_card_by_mtgo_id_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(id): Path<u32>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    card_by_id_response(cards.get_by_mtgo_id(id), &id.to_string())
+}
+
+#[instrument(skip(store))]
+pub async fn get_card_by_arena_id_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(id): Path<u32>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    card_by_id_response(cards.get_by_arena_id(id), &id.to_string())
+}
+
+#[instrument(skip_all)]
+pub async fn batch_cards_handler(
+    State(store): State<Arc<CardStore>>,
+    Query(preference): Query<PrintingPreference>,
+    Json(request): Json<BatchRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    if request.identifiers.len() > MAX_BATCH_SIZE {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("At most {} identifiers per request", MAX_BATCH_SIZE),
+        ));
+    }
+
+    let cards = store.current();
+    let result = lookup_cards(request.identifiers, &cards, &preference);
+    debug!(
+        found_count = result.found.len(),
+        not_found_count = result.not_found.len(),
+        "Batch lookup completed"
+    );
+    Ok(Json(result).into_response())
+}
+
+const DEFAULT_AUTOCOMPLETE_LIMIT: usize = 10;
+const MAX_AUTOCOMPLETE_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct AutocompleteQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct AutocompleteResponse {
+    names: Vec<String>,
+}
+
+#[instrument(skip(store))]
+pub async fn autocomplete_handler(
+    State(store): State<Arc<CardStore>>,
+    Query(query): Query<AutocompleteQuery>,
+) -> Json<AutocompleteResponse> {
+    let cards = store.current();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+        .min(MAX_AUTOCOMPLETE_LIMIT);
+    let names = cards.autocomplete_names(&query.q, limit);
+    debug!(results = names.len(), "Autocomplete completed");
+    Json(AutocompleteResponse { names })
+}
+
+const DEFAULT_SEARCH_PAGE_SIZE: usize = 50;
+const MAX_SEARCH_PAGE_SIZE: usize = 175;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
+#[instrument(skip(store))]
+pub async fn search_cards_handler(
+    State(store): State<Arc<CardStore>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let cards = store.current();
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_SEARCH_PAGE_SIZE)
+        .clamp(1, MAX_SEARCH_PAGE_SIZE);
+    // Scanning every card can take a while, so it runs off the async workers
+    let search = tokio::task::spawn_blocking(move || {
+        match search_cards(&cards, &query.q, query.page.unwrap_or(1), page_size) {
+            Ok(result) => {
+                debug!(total_cards = result.total_cards, "Search completed");
+                Ok(Json(result).into_response())
+            }
+            Err(error) => {
+                debug!(%error, "Invalid search query");
+                Err((StatusCode::BAD_REQUEST, error.to_string()))
+            }
+        }
+    });
+    search.await.unwrap_or_else(|join_error| {
+        error!(%join_error, "Card search task failed");
+        Err((StatusCode::INTERNAL_SERVER_ERROR, join_error.to_string()))
+    })
+}
+
+#[instrument(skip(store))]
+pub async fn list_sets_handler(State(store): State<Arc<CardStore>>) -> Response {
+    let cards = store.current();
+    Json(cards.sets().all()).into_response()
+}
+
+#[instrument(skip(store))]
+pub async fn get_set_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(code): Path<String>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    match cards.sets().get(&code) {
+        Some(set) => Ok(Json(set).into_response()),
+        None => {
+            warn!(set_code = %code, "Set not found");
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
+/// Lists the printings in a set by collector number, paginated like search results.
+#[instrument(skip(store))]
+pub async fn get_set_cards_handler(
+    State(store): State<Arc<CardStore>>,
+    Path(code): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    if cards.sets().get(&code).is_none() {
+        warn!(set_code = %code, "Set not found");
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_SEARCH_PAGE_SIZE)
+        .clamp(1, MAX_SEARCH_PAGE_SIZE);
+    let set_cards = cards.cards_in_set(&code);
+    let result = SearchResult::paginate(&set_cards, query.page.unwrap_or(1), page_size);
+    debug!(total_cards = result.total_cards, "Set cards listed");
+    Ok(Json(result).into_response())
+}
+
 #[instrument(skip_all)]
 pub async fn resolve_deck_handler(
-    State(cards): State<CardMap>,
+    State(store): State<Arc<CardStore>>,
+    Query(preference): Query<PrintingPreference>,
+    Query(FormatQuery { format }): Query<FormatQuery>,
     deck_text: String,
-) -> Result<Json<DeckResolveResult>, StatusCode> {
-    let result = resolve_deck_list(&deck_text, &cards);
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    let result = resolve_deck_list(&deck_text, format, &cards, &preference);
     debug!(
         entries_count = result.entries.len(),
         errors_count = result.errors.len(),
         total_cards = result.total_cards,
         "Deck processing completed"
     );
-    Ok(Json(result))
+    Ok(Json(result).into_response())
+}
+
+/// Resolves a deck list and returns it as a file for another tool.
+#[instrument(skip_all)]
+pub async fn export_deck_handler(
+    State(store): State<Arc<CardStore>>,
+    Query(preference): Query<PrintingPreference>,
+    Query(FormatQuery { format }): Query<FormatQuery>,
+    Query(ExportQuery { to }): Query<ExportQuery>,
+    deck_text: String,
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    let result = resolve_deck_list(&deck_text, format, &cards, &preference);
+    // Header values are kept to plain ASCII
+    let file_name: String = result
+        .name
+        .as_deref()
+        .unwrap_or("deck")
+        .chars()
+        .filter(|&c| (c.is_ascii_graphic() || c == ' ') && c != '"')
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, to.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", file_name, to.extension()),
+            ),
+        ],
+        to.export(&result, &cards),
+    )
+        .into_response())
 }
 
 #[instrument(skip_all)]
 pub async fn diff_deck_handler(
-    State(cards): State<CardMap>,
+    State(store): State<Arc<CardStore>>,
+    Query(preference): Query<PrintingPreference>,
     Json(request): Json<DeckDiffRequest>,
-) -> Result<Json<DeckDiffResult>, StatusCode> {
-    let result = diff_decks(&request.deck_list_1, &request.deck_list_2, &cards);
+) -> Result<Response, StatusCode> {
+    let cards = store.current();
+    let result = diff_decks(
+        resolve_deck_list(&request.deck_list_1, request.format_1, &cards, &preference),
+        resolve_deck_list(&request.deck_list_2, request.format_2, &cards, &preference),
+    );
     debug!(
         added_count = result.added.len(),
         removed_count = result.removed.len(),
@@ -78,15 +366,98 @@ pub async fn diff_deck_handler(
         errors_deck_2 = result.errors_deck_2.len(),
         "Deck diff processing completed"
     );
-    Ok(Json(result))
+    Ok(Json(result).into_response())
+}
+
+#[derive(Serialize)]
+pub struct ReloadResponse {
+    cards_loaded: usize,
+    card_data: Option<CardDataSource>,
+}
+
+#[instrument(skip(store))]
+pub async fn reload_handler(
+    State(store): State<Arc<CardStore>>,
+) -> Result<Json<ReloadResponse>, (StatusCode, String)> {
+    match tokio::task::spawn_blocking(move || store.reload()).await {
+        Ok(Ok(cards)) => Ok(Json(ReloadResponse {
+            cards_loaded: cards.len(),
+            card_data: cards.source().cloned(),
+        })),
+        Ok(Err(error)) => Err((StatusCode::INTERNAL_SERVER_ERROR, error.to_string())),
+        Err(join_error) => {
+            error!(%join_error, "Card reload task failed");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, join_error.to_string()))
+        }
+    }
+}
+
+/// Compares two byte strings in time that depends only on their lengths, so response times do
+/// not reveal how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .fold(0, |diff, (x, y)| std::hint::black_box(diff | (x ^ y)))
+            == 0
 }
 
-pub fn create_router(cards: CardMap) -> Router {
-    Router::new()
+/// Rejects requests without `Authorization: Bearer <token>`.
+async fn require_admin_token(
+    token: Arc<str>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()));
+    if !authorized {
+        warn!("Rejected admin request without a valid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+/// The API routes, open to any origin, and `/admin/reload` when an admin token is configured.
+/// The admin routes are left out of CORS, so web pages cannot call them.
+pub fn create_router(store: Arc<CardStore>, admin_token: Option<String>) -> Router {
+    let router = Router::new()
         .route("/health", get(health_check_handler))
+        .route("/cards/autocomplete", get(autocomplete_handler))
+        .route("/cards/search", get(search_cards_handler))
+        .route("/cards/batch", post(batch_cards_handler))
+        .route("/cards/id/:id", get(get_card_by_scryfall_id_handler))
+        .route(
+            "/cards/oracle/:oracle_id",
+            get(get_card_by_oracle_id_handler),
+        )
+        .route("/cards/mtgo/:id", get(get_card_by_mtgo_id_handler))
+        .route("/cards/arena/:id", get(get_card_by_arena_id_handler))
         .route("/cards/:name", get(get_card_by_name_handler))
+        .route("/cards/:name/printings", get(get_card_printings_handler))
+        .route("/sets", get(list_sets_handler))
+        .route("/sets/:code", get(get_set_handler))
+        .route("/sets/:code/cards", get(get_set_cards_handler))
         .route("/deck/resolve", post(resolve_deck_handler))
+        .route("/deck/export", post(export_deck_handler))
         .route("/deck/diff", post(diff_deck_handler))
-        .layer(CorsLayer::permissive())
-        .with_state(cards)
+        .layer(CorsLayer::permissive());
+
+    let router = match admin_token {
+        Some(token) => {
+            let token: Arc<str> = token.into();
+            router.merge(
+                Router::new()
+                    .route("/admin/reload", post(reload_handler))
+                    .route_layer(middleware::from_fn(move |request, next| {
+                        require_admin_token(Arc::clone(&token), request, next)
+                    })),
+            )
+        }
+        None => router,
+    };
+    router.with_state(store)
 }
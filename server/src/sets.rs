@@ -0,0 +1,157 @@
+//! The catalog of Magic sets.
+//!
+//! Set details come from Scryfall's set list, saved as `sets.json` next to the card data (the
+//! `refresh-data` command downloads it). Sets that only appear on cards, or all sets when the file
+//! is missing, are described from the cards themselves.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::cards::Card;
+use crate::intern::IStr;
+
+/// File name of the set list in the card data directory.
+pub const SETS_FILE_NAME: &str = "sets.json";
+
+/// A set object from Scryfall, see <https://scryfall.com/docs/api/sets>.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CardSet {
+    pub code: IStr,
+    pub name: IStr,
+    pub set_type: IStr,
+    pub released_at: Option<IStr>,
+    #[serde(default)]
+    pub card_count: u32,
+    pub parent_set_code: Option<IStr>,
+    #[serde(default)]
+    pub digital: bool,
+    pub icon_svg_uri: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetList {
+    data: Vec<CardSet>,
+}
+
+/// Reads a set list as returned by Scryfall's `/sets` endpoint.
+pub fn read_sets_file(
+    path: &Path,
+) -> Result<Vec<CardSet>, Box<dyn std::error::Error + Send + Sync>> {
+    let list: SetList = sonic_rs::from_reader(BufReader::new(File::open(path)?))?;
+    Ok(list.data)
+}
+
+/// All known sets, looked up by code ignoring case.
+#[derive(Debug, Default)]
+pub struct SetCatalog {
+    // Most recent release first
+    sets: Vec<CardSet>,
+    by_code: HashMap<IStr, usize>,
+}
+
+impl SetCatalog {
+    /// Builds the catalog from `sets`, adding any set that only appears on `cards`.
+    pub fn new(sets: Vec<CardSet>, cards: &[Card]) -> Self {
+        let mut by_code: HashMap<IStr, CardSet> = sets
+            .into_iter()
+            .map(|set| (IStr::from(set.code.to_lowercase()), set))
+            .collect();
+
+        let mut derived: HashMap<IStr, CardSet> = HashMap::new();
+        for card in cards {
+            let code = IStr::from(card.set.to_lowercase());
+            if code.is_empty() || by_code.contains_key(&code) {
+                continue;
+            }
+            let set = derived.entry(code).or_insert_with(|| CardSet {
+                code: card.set.clone(),
+                name: card.set_name.clone(),
+                set_type: card.set_type.clone(),
+                released_at: Some(card.released_at.clone()),
+                ..Default::default()
+            });
+            set.card_count += 1;
+            if set
+                .released_at
+                .as_ref()
+                .is_some_and(|released_at| card.released_at < *released_at)
+            {
+                set.released_at = Some(card.released_at.clone());
+            }
+        }
+        by_code.extend(derived);
+
+        let mut sets: Vec<CardSet> = by_code.into_values().collect();
+        sets.sort_by(|a, b| {
+            b.released_at
+                .cmp(&a.released_at)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        let by_code = sets
+            .iter()
+            .enumerate()
+            .map(|(index, set)| (IStr::from(set.code.to_lowercase()), index))
+            .collect();
+
+        Self { sets, by_code }
+    }
+
+    pub fn get(&self, code: &str) -> Option<&CardSet> {
+        self.by_code
+            .get(code.to_lowercase().as_str())
+            .map(|&index| &self.sets[index])
+    }
+
+    /// Every set, most recent release first.
+    pub fn all(&self) -> &[CardSet] {
+        &self.sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_merges_set_list_with_sets_from_cards() {
+        let card = |set: &str, released_at: &str| Card {
+            set: set.into(),
+            set_name: format!("{} name", set).into(),
+            set_type: "expansion".into(),
+            released_at: released_at.into(),
+            ..Default::default()
+        };
+        let sets: Vec<CardSet> = sonic_rs::from_str::<SetList>(
+            r#"{"object": "list", "data": [{"object": "set", "code": "tla",
+            "name": "Avatar: The Last Airbender", "set_type": "expansion",
+            "released_at": "2025-11-21", "card_count": 394, "digital": false,
+            "icon_svg_uri": "https://svgs.scryfall.io/sets/tla.svg"}]}"#,
+        )
+        .unwrap()
+        .data;
+        let catalog = SetCatalog::new(
+            sets,
+            &[
+                card("tla", "2025-11-21"),
+                card("plst", "2021-06-18"),
+                card("plst", "2020-01-01"),
+            ],
+        );
+
+        let tla = catalog.get("TLA").unwrap();
+        assert_eq!(tla.name, "Avatar: The Last Airbender");
+        assert_eq!(tla.card_count, 394);
+
+        let plst = catalog.get("plst").unwrap();
+        assert_eq!(plst.name, "plst name");
+        assert_eq!(plst.card_count, 2);
+        assert_eq!(plst.released_at.as_deref(), Some("2020-01-01"));
+
+        let codes: Vec<&str> = catalog.all().iter().map(|set| set.code.as_str()).collect();
+        assert_eq!(codes, vec!["tla", "plst"]);
+        assert!(catalog.get("xyz").is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{error, info};
+
+use crate::cards::{CardMap, load_cards};
+
+/// The currently loaded cards, replaceable at runtime.
+///
+/// Requests take a snapshot with [`CardStore::current`] and keep using it until they finish, so
+/// a reload never changes the data underneath an in-flight request. New requests see the new
+/// data as soon as the swap happens.
+#[derive(Debug)]
+pub struct CardStore {
+    current: RwLock<CardMap>,
+    card_data: PathBuf,
+    use_cache: bool,
+    // Serializes reloads so two triggers do not parse the data twice at once
+    reload_lock: Mutex<()>,
+}
+
+impl CardStore {
+    pub fn new(cards: CardMap, card_data: PathBuf, use_cache: bool) -> Self {
+        Self {
+            current: RwLock::new(cards),
+            card_data,
+            use_cache,
+            reload_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn current(&self) -> CardMap {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Loads the card data again from the configured path and swaps it in.
+    ///
+    /// A directory is searched again, so a newer bulk file is picked up. On failure the current
+    /// data stays in place.
+    pub fn reload(&self) -> Result<CardMap, Box<dyn std::error::Error + Send + Sync>> {
+        let _guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        info!(path = %self.card_data.display(), "Reloading card data");
+        let cards = match load_cards(&self.card_data, self.use_cache) {
+            Ok(cards) => cards,
+            Err(error) => {
+                error!(%error, "Failed to reload card data, keeping the current data");
+                return Err(error);
+            }
+        };
+
+        *self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::clone(&cards);
+        info!(cards_loaded = cards.len(), "Reloaded card data");
+        Ok(cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_reload_swaps_data_and_keeps_it_on_failure() {
+        let dir = std::env::temp_dir().join(format!("mtg-card-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let card_json = |name: &str| {
+            format!(
+                r#"{{"id": "{name}", "name": "{name}", "lang": "en", "released_at": "2020-01-01",
+                "layout": "normal", "rarity": "common", "set": "tst", "set_name": "Test",
+                "set_type": "core", "collector_number": "1", "scryfall_uri": "",
+                "image_status": "missing"}}"#
+            )
+        };
+
+        fs::write(
+            dir.join("oracle-cards-20250101000000.json"),
+            format!("[{}]", card_json("Forest")),
+        )
+        .unwrap();
+        let store = CardStore::new(load_cards(&dir, false).unwrap(), dir.clone(), false);
+        let before = store.current();
+        assert_eq!(before.len(), 1);
+
+        fs::write(
+            dir.join("oracle-cards-20250201000000.json"),
+            format!("[{}, {}]", card_json("Forest"), card_json("Island")),
+        )
+        .unwrap();
+        store.reload().unwrap();
+        assert_eq!(store.current().len(), 2);
+        // Snapshots taken before the reload are unaffected
+        assert_eq!(before.len(), 1);
+
+        fs::write(dir.join("oracle-cards-20250301000000.json"), "not json").unwrap();
+        assert!(store.reload().is_err());
+        assert_eq!(store.current().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}